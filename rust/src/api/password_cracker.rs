@@ -2,11 +2,126 @@ use anyhow::{anyhow, Result};
 use crate::frb_generated::StreamSink;
 use rayon::prelude::*;
 use std::io::{Cursor, Read};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use zip::ZipArchive;
 use crc32fast;
+use xxhash_rust::xxh3::xxh3_64;
+use pbkdf2;
+use hmac;
+use sha1;
+use aes;
+use ctr;
+use flate2;
+use region;
+
+// ============================================================
+// PLAINTEXT LOGGING SWITCH
+// ============================================================
+/// Liga/desliga o preview de conteúdo decriptado nos logs de debug
+/// (`try_unlock_fast`, `test_specific_password`). Desligado por padrão
+/// em release — despejar bytes decriptados no stdout derruba o
+/// propósito de uma ferramenta de recuperação de senha real.
+static PLAINTEXT_LOGGING: OnceLock<AtomicBool> = OnceLock::new();
+
+fn plaintext_logging_enabled() -> bool {
+    PLAINTEXT_LOGGING
+        .get_or_init(|| AtomicBool::new(cfg!(debug_assertions)))
+        .load(Ordering::Relaxed)
+}
+
+/// Liga/desliga o preview de conteúdo em texto claro (ver `plaintext_logging_enabled`)
+pub fn set_plaintext_logging(enabled: bool) {
+    PLAINTEXT_LOGGING
+        .get_or_init(|| AtomicBool::new(cfg!(debug_assertions)))
+        .store(enabled, Ordering::Relaxed);
+}
+
+// ============================================================
+// LOCKED BUFFERS
+// ============================================================
+/// Buffer de bytes com as páginas de memória travadas via `mlock` (não
+/// vai para swap) e zerado byte a byte no `Drop` via `ptr::write_volatile`
+/// (impede o compilador de otimizar o zero-out). Usado para o `read_buf`
+/// reutilizável de `try_unlock_fast` e para o buffer de senha do
+/// odômetro de força bruta.
+struct LockedBuffer {
+    data: Vec<u8>,
+    guard: Option<region::LockGuard>,
+}
+
+impl LockedBuffer {
+    fn new(data: Vec<u8>) -> Self {
+        let mut buf = Self { data, guard: None };
+        buf.relock();
+        buf
+    }
+
+    fn with_capacity(cap: usize) -> Self {
+        Self::new(Vec::with_capacity(cap))
+    }
+
+    /// Reaplica o mlock no endereço atual do buffer. Necessário depois de
+    /// qualquer operação que possa realocar o Vec (ex: `read_to_end` além
+    /// da capacidade reservada) — mlock trava páginas por endereço, então
+    /// uma realocação invalida a trava anterior. Falha de mlock (ex:
+    /// limite de páginas travadas do processo) não é fatal: seguimos sem
+    /// a trava, mas o zero-out no Drop continua valendo de qualquer forma.
+    fn relock(&mut self) {
+        self.guard = if self.data.capacity() == 0 {
+            None
+        } else {
+            region::lock(self.data.as_ptr(), self.data.capacity()).ok()
+        };
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Acesso ao `Vec<u8>` interno para operações como `read_to_end` que
+    /// exigem `&mut Vec<u8>`. O chamador deve invocar `relock()` depois,
+    /// caso a operação possa ter realocado o buffer.
+    fn inner_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.data
+    }
+
+    /// Zera toda a capacidade alocada (não só `len()`) e esvazia o buffer.
+    /// `clear()` sozinho só zera o comprimento lógico — os bytes antigos
+    /// continuam intactos na alocação até serem sobrescritos, então um
+    /// `read_buf` reciclado entre tentativas (`len` volta a 0 a cada reuso)
+    /// nunca teria seu conteúdo anterior realmente apagado da memória.
+    fn zero_and_clear(&mut self) {
+        Self::zero_allocation(&mut self.data);
+        self.data.clear();
+    }
+
+    /// Zera byte a byte até `capacity()`, não só `len()` — cobre tanto o
+    /// conteúdo lógico quanto os bytes que sobraram de usos anteriores
+    /// na mesma alocação (ex: depois de um `clear()` que não passou por
+    /// `zero_and_clear`).
+    fn zero_allocation(data: &mut Vec<u8>) {
+        let cap = data.capacity();
+        if cap == 0 {
+            return;
+        }
+        let ptr = data.as_mut_ptr();
+        for i in 0..cap {
+            unsafe { std::ptr::write_volatile(ptr.add(i), 0) };
+        }
+    }
+}
+
+impl Drop for LockedBuffer {
+    fn drop(&mut self) {
+        Self::zero_allocation(&mut self.data);
+    }
+}
 
 // ============================================================
 // GLOBAL PAUSE STATE
@@ -47,6 +162,59 @@ pub struct CrackProgress {
     pub elapsed_seconds: u64,
     pub passwords_per_second: f64,
     pub phase: String,
+    pub stats: CrackStats,
+}
+
+/// Estatísticas detalhadas de uma execução de crack, para a UI mostrar
+/// progresso por fase e uma estimativa de tempo restante
+#[derive(Debug, Clone, Default)]
+pub struct CrackStats {
+    pub dictionary_attempts: u64,
+    pub combinator_attempts: u64,
+    pub bruteforce_attempts: u64,
+    pub duplicate_count: u64,
+    pub false_positive_count: u64,
+    /// Percentual do keyspace já percorrido (0.0-100.0), quando calculável
+    pub keyspace_percent: f64,
+    /// Estimativa de tempo restante com base em `passwords_per_second` atual
+    pub eta_seconds: Option<u64>,
+}
+
+/// Contadores atômicos por fase, compartilhados entre as fases da
+/// quebra para alimentar `CrackStats` sem recalcular nada depois
+#[derive(Default)]
+struct PhaseCounters {
+    dictionary_attempts: AtomicU64,
+    combinator_attempts: AtomicU64,
+    duplicate_count: AtomicU64,
+    false_positive_count: AtomicU64,
+}
+
+impl PhaseCounters {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Monta o `CrackStats` público a partir dos contadores internos.
+    /// `bruteforce_attempts` é derivado por subtração do total combinado,
+    /// já que as fases de brute force/markov/mask somam direto em `attempts`.
+    fn to_stats(&self, total_attempts: u64, keyspace_percent: f64, eta_seconds: Option<u64>) -> CrackStats {
+        let dictionary_attempts = self.dictionary_attempts.load(Ordering::Relaxed);
+        let combinator_attempts = self.combinator_attempts.load(Ordering::Relaxed);
+        let bruteforce_attempts = total_attempts
+            .saturating_sub(dictionary_attempts)
+            .saturating_sub(combinator_attempts);
+
+        CrackStats {
+            dictionary_attempts,
+            combinator_attempts,
+            bruteforce_attempts,
+            duplicate_count: self.duplicate_count.load(Ordering::Relaxed),
+            false_positive_count: self.false_positive_count.load(Ordering::Relaxed),
+            keyspace_percent,
+            eta_seconds,
+        }
+    }
 }
 
 /// Resultado da quebra de senha
@@ -69,6 +237,29 @@ pub struct CrackConfig {
     pub use_symbols: bool,
     pub use_dictionary: bool,
     pub custom_words: Vec<String>,
+    /// Regras de mutação no estilo John-the-Ripper/hashcat (ex: "c $1", "u $!").
+    /// Vazio = mantém o comportamento built-in de `generate_mutations`.
+    pub rules: Vec<String>,
+    /// Número de palavras combinadas no ataque combinator (0/1 = desabilitado).
+    /// Ex: 2 testa `word1+word2`, 3 testa `word1+word2+word3`.
+    pub combinator_depth: usize,
+    /// Separadores testados entre as palavras combinadas (ex: "", "-", "_").
+    pub separators: Vec<String>,
+    /// Quando true, a Fase 2 enumera por probabilidade (modelo Markov de
+    /// ordem 1 treinado na wordlist embutida) em vez do odômetro puro.
+    pub markov: bool,
+    /// Máscara com charset por posição (ex: "?u?u?u?u?d?d?d?d"), usando
+    /// `?l`/`?u`/`?d`/`?s` + literais. Quando presente, substitui o
+    /// laço `min_length..=max_length` da Fase 2.
+    pub mask: Option<String>,
+    /// Caminho para salvar/carregar checkpoints da Fase 2. Se o arquivo já
+    /// existir e o hash bater com `file_bytes`, a busca retoma no comprimento
+    /// salvo em vez de recomeçar em `min_length`; esse comprimento é varrido
+    /// de novo desde o primeiro candidato (o índice de chunk salvo não é
+    /// usado como ponto de retomada).
+    pub checkpoint_path: Option<String>,
+    /// Intervalo entre checkpoints automáticos (segundos)
+    pub checkpoint_interval_secs: u64,
 }
 
 impl Default for CrackConfig {
@@ -82,8 +273,174 @@ impl Default for CrackConfig {
             use_symbols: false,
             use_dictionary: true,
             custom_words: Vec::new(),
+            rules: Vec::new(),
+            combinator_depth: 0,
+            separators: vec![String::new()],
+            markov: false,
+            mask: None,
+            checkpoint_path: None,
+            checkpoint_interval_secs: 30,
+        }
+    }
+}
+
+// ============================================================
+// RULE ENGINE — mutações configuráveis estilo John-the-Ripper
+// ============================================================
+//
+// Linguagem suportada (uma palavra de regra por linha, comandos
+// aplicados em sequência à palavra base):
+//   l     lowercase a palavra inteira
+//   u     UPPERCASE a palavra inteira
+//   c     Capitalize (primeira letra maiúscula)
+//   $X    append do caractere X ao final
+//   ^X    prepend do caractere X no início
+//   sXY   substitui todas as ocorrências de X por Y
+//   r     reverse (inverte a palavra)
+//   d     duplicate (word -> wordword)
+//   tN    toggle case na posição N (0-indexado)
+
+/// Um comando individual da linguagem de regras
+#[derive(Debug, Clone, Copy)]
+enum RuleOp {
+    Lowercase,
+    Uppercase,
+    Capitalize,
+    Append(char),
+    Prepend(char),
+    Substitute(char, char),
+    Reverse,
+    Duplicate,
+    ToggleAt(usize),
+}
+
+/// Parseia uma linha de regra em uma sequência de `RuleOp`.
+/// Tokens desconhecidos ou malformados são ignorados silenciosamente,
+/// assim uma regra parcialmente inválida ainda aplica o que entendeu.
+fn parse_rule_line(line: &str) -> Vec<RuleOp> {
+    let chars: Vec<char> = line.trim().chars().collect();
+    let mut ops = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            'l' => {
+                ops.push(RuleOp::Lowercase);
+                i += 1;
+            }
+            'u' => {
+                ops.push(RuleOp::Uppercase);
+                i += 1;
+            }
+            'c' => {
+                ops.push(RuleOp::Capitalize);
+                i += 1;
+            }
+            'r' => {
+                ops.push(RuleOp::Reverse);
+                i += 1;
+            }
+            'd' => {
+                ops.push(RuleOp::Duplicate);
+                i += 1;
+            }
+            '$' if i + 1 < chars.len() => {
+                ops.push(RuleOp::Append(chars[i + 1]));
+                i += 2;
+            }
+            '^' if i + 1 < chars.len() => {
+                ops.push(RuleOp::Prepend(chars[i + 1]));
+                i += 2;
+            }
+            's' if i + 2 < chars.len() => {
+                ops.push(RuleOp::Substitute(chars[i + 1], chars[i + 2]));
+                i += 3;
+            }
+            't' if i + 1 < chars.len() && chars[i + 1].is_ascii_digit() => {
+                let pos = chars[i + 1].to_digit(10).unwrap_or(0) as usize;
+                ops.push(RuleOp::ToggleAt(pos));
+                i += 2;
+            }
+            ' ' => {
+                // separador entre comandos numa mesma linha
+                i += 1;
+            }
+            _ => {
+                // token desconhecido, pula um caractere
+                i += 1;
+            }
         }
     }
+
+    ops
+}
+
+/// Aplica uma sequência de `RuleOp` a uma palavra base, na ordem.
+fn apply_rule_ops(ops: &[RuleOp], word: &str) -> String {
+    let mut result = word.to_string();
+
+    for op in ops {
+        result = match op {
+            RuleOp::Lowercase => result.to_lowercase(),
+            RuleOp::Uppercase => result.to_uppercase(),
+            RuleOp::Capitalize => {
+                let mut s = result.clone();
+                if let Some(first) = s.get_mut(0..1) {
+                    first.make_ascii_uppercase();
+                }
+                s
+            }
+            RuleOp::Append(c) => format!("{}{}", result, c),
+            RuleOp::Prepend(c) => format!("{}{}", c, result),
+            RuleOp::Substitute(from, to) => result.chars().map(|c| if c == *from { *to } else { c }).collect(),
+            RuleOp::Reverse => result.chars().rev().collect(),
+            RuleOp::Duplicate => format!("{}{}", result, result),
+            RuleOp::ToggleAt(pos) => {
+                let mut chars: Vec<char> = result.chars().collect();
+                if let Some(c) = chars.get_mut(*pos) {
+                    if c.is_uppercase() {
+                        *c = c.to_ascii_lowercase();
+                    } else {
+                        *c = c.to_ascii_uppercase();
+                    }
+                }
+                chars.into_iter().collect()
+            }
+        };
+    }
+
+    result
+}
+
+/// Programa de mutação compilado a partir de `CrackConfig::rules`.
+/// Cada linha de regra vira um candidato por palavra base; uma
+/// instância vazia sinaliza "usar o comportamento built-in".
+#[derive(Debug, Clone, Default)]
+pub struct MutationRules {
+    compiled: Vec<Vec<RuleOp>>,
+}
+
+impl MutationRules {
+    /// Compila as linhas de regra fornecidas pelo usuário
+    pub fn parse(rules: &[String]) -> Self {
+        let compiled = rules
+            .iter()
+            .map(|line| parse_rule_line(line))
+            .filter(|ops| !ops.is_empty())
+            .collect();
+
+        Self { compiled }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.compiled.is_empty()
+    }
+
+    /// Aplica todas as regras compiladas à palavra base, uma candidata por regra
+    fn apply(&self, word: &str) -> Vec<String> {
+        self.compiled.iter().map(|ops| apply_rule_ops(ops, word)).collect()
+    }
 }
 
 // ============================================================
@@ -194,7 +551,7 @@ const EMBEDDED_WORDLIST: &str = include_str!("password_list.txt");
 /// Gera mutações de uma senha base para aumentar cobertura
 /// Ex: "password" → ["PASSWORD", "Password", "p@ssword", "password1", "password123", ...]
 fn generate_mutations(word: &str) -> Vec<String> {
-    let mut mutations = Vec::with_capacity(12);
+    let mut mutations = Vec::with_capacity(19);
 
     // Original
     mutations.push(word.to_string());
@@ -216,6 +573,11 @@ fn generate_mutations(word: &str) -> Vec<String> {
         mutations.push(format!("{}{}", word, suffix));
     }
 
+    // Sufixos de ano, comuns em senhas do tipo "nome2024"/"nome24"
+    for year in &["2024", "2025", "23", "24", "25"] {
+        mutations.push(format!("{}{}", word, year));
+    }
+
     // L33t speak básico (a→@, e→3, o→0, i→1, s→$)
     let leet: String = word
         .chars()
@@ -235,14 +597,21 @@ fn generate_mutations(word: &str) -> Vec<String> {
     mutations
 }
 
+/// Tamanho do lote despachado por vez para os workers paralelos — como
+/// em `combinator_attack`, evita materializar todas as mutações (wordlist
+/// * regras) em um único `Vec` antes de testar.
+const DICTIONARY_BATCH_SIZE: usize = 50_000;
+
 /// Executa o dictionary attack em paralelo
 /// Retorna Some(password) se encontrou, None caso contrário
 fn dictionary_attack(
     zip_data: &[u8],
     target_entry: usize,
     custom_words: &[String],
+    rules: &MutationRules,
     progress_sink: &StreamSink<CrackProgress>,
     attempts: &AtomicU64,
+    phase_counters: &PhaseCounters,
 ) -> Option<String> {
     // Parse da wordlist embutida (linhas do arquivo .txt)
     let wordlist: Vec<&str> = EMBEDDED_WORDLIST
@@ -251,175 +620,1048 @@ fn dictionary_attack(
         .filter(|l| !l.is_empty())
         .collect();
 
-    // Coleta todas as senhas candidatas (wordlist + mutações + custom)
-    let mut candidates: Vec<String> = Vec::with_capacity(
-        wordlist.len() * 12 + custom_words.len() * 12,
-    );
+    let mutate = |word: &str| -> Vec<String> {
+        if rules.is_empty() {
+            generate_mutations(word)
+        } else {
+            rules.apply(word)
+        }
+    };
 
-    for word in &wordlist {
-        candidates.extend(generate_mutations(word));
+    let _ = progress_sink.add(CrackProgress {
+        attempts: 0,
+        current_password: format!(
+            "Dicionário: testando mutações de {} palavras-base...",
+            wordlist.len() + custom_words.len()
+        ),
+        elapsed_seconds: 0,
+        passwords_per_second: 0.0,
+        phase: "dictionary".to_string(),
+        stats: CrackStats::default(),
+    });
+
+    let found_flag = AtomicBool::new(false);
+    // Hash xxh3 de 64 bits em vez de `sort_unstable` + `dedup` num Vec
+    // gigante: descarta duplicatas em tempo constante à medida que as
+    // mutações são geradas, sem nunca materializar a lista inteira.
+    let mut seen: std::collections::HashSet<u64> = std::collections::HashSet::new();
+    let mut batch: Vec<String> = Vec::with_capacity(DICTIONARY_BATCH_SIZE);
+
+    for word in wordlist.iter().copied().chain(custom_words.iter().map(String::as_str)) {
+        for candidate in mutate(word) {
+            if found_flag.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            if !seen.insert(xxh3_64(candidate.as_bytes())) {
+                phase_counters.duplicate_count.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+
+            batch.push(candidate);
+            if batch.len() < DICTIONARY_BATCH_SIZE {
+                continue;
+            }
+
+            if let Some(pwd) = search_candidate_batch(
+                zip_data,
+                target_entry,
+                &batch,
+                &found_flag,
+                attempts,
+                phase_counters,
+                &phase_counters.dictionary_attempts,
+            ) {
+                return Some(pwd);
+            }
+            batch.clear();
+        }
     }
-    for word in custom_words {
-        candidates.extend(generate_mutations(word));
+
+    if !batch.is_empty() {
+        if let Some(pwd) = search_candidate_batch(
+            zip_data,
+            target_entry,
+            &batch,
+            &found_flag,
+            attempts,
+            phase_counters,
+            &phase_counters.dictionary_attempts,
+        ) {
+            return Some(pwd);
+        }
     }
 
-    // Deduplica
-    candidates.sort_unstable();
-    candidates.dedup();
+    None
+}
 
-    let total = candidates.len();
-    let found_flag = AtomicBool::new(false);
-    let dict_attempts = AtomicU64::new(0);
+// ============================================================
+// COMBINATOR ATTACK — junta 2-3 palavras do dicionário
+// Cobre senhas tipo "sunflower42", "redhouse!" que o dictionary
+// attack sozinho (uma palavra + mutação) nunca cobriria.
+// ============================================================
+
+/// Limita o número de palavras-base usadas no produto de pares (profundidade 2).
+const COMBINATOR_WORD_CAP: usize = 300;
+
+/// Limita separadamente o número de palavras-base usadas no produto de
+/// triplas (profundidade 3): `COMBINATOR_WORD_CAP` é dimensionado para o
+/// caso N² e reusá-lo em N³ faz o espaço explodir bem além do que o cap
+/// pretendia conter (300³ × 5 sufixos ≈ 135M candidatos por separador,
+/// contra ~450K em N²). Escolhido para manter `cap³` na mesma ordem de
+/// grandeza de `COMBINATOR_WORD_CAP²` hoje.
+const COMBINATOR_TRIPLE_WORD_CAP: usize = 40;
+
+/// Tamanho do lote processado por vez — streaming em vez de
+/// materializar o produto inteiro em um único `Vec`.
+const COMBINATOR_BATCH_SIZE: usize = 50_000;
+
+/// Gera o produto de `depth` palavras (2 ou 3) separadas por `separators`,
+/// com um sufixo numérico opcional no final, como um iterator preguiçoso.
+fn combinator_candidates<'a>(
+    words: &'a [String],
+    depth: usize,
+    separators: &'a [String],
+) -> Box<dyn Iterator<Item = String> + 'a> {
+    const DIGIT_SUFFIXES: [&str; 5] = ["", "1", "12", "123", "42"];
+
+    let pairs = words.iter().flat_map(move |a| {
+        words.iter().flat_map(move |b| {
+            separators.iter().flat_map(move |sep| {
+                DIGIT_SUFFIXES
+                    .iter()
+                    .map(move |suf| format!("{}{}{}{}", a, sep, b, suf))
+            })
+        })
+    });
+
+    if depth < 3 {
+        return Box::new(pairs);
+    }
+
+    // N³ explode muito mais rápido que N² — usa um cap bem menor aqui,
+    // por cima do que já veio cortado em COMBINATOR_WORD_CAP
+    let triple_words = &words[..words.len().min(COMBINATOR_TRIPLE_WORD_CAP)];
+
+    let triples = triple_words.iter().flat_map(move |a| {
+        triple_words.iter().flat_map(move |b| {
+            triple_words.iter().flat_map(move |c| {
+                separators.iter().flat_map(move |sep| {
+                    DIGIT_SUFFIXES
+                        .iter()
+                        .map(move |suf| format!("{}{}{}{}{}{}", a, sep, b, sep, c, suf))
+                })
+            })
+        })
+    });
+
+    Box::new(pairs.chain(triples))
+}
+
+/// Executa o combinator attack em lotes paralelos
+/// Retorna Some(password) se encontrou, None caso contrário
+fn combinator_attack(
+    zip_data: &[u8],
+    target_entry: usize,
+    custom_words: &[String],
+    depth: usize,
+    separators: &[String],
+    progress_sink: &StreamSink<CrackProgress>,
+    attempts: &AtomicU64,
+    phase_counters: &PhaseCounters,
+) -> Option<String> {
+    if depth < 2 {
+        return None;
+    }
+
+    // custom_words vem primeiro e nunca é cortado pelo cap: é o que o
+    // usuário pediu explicitamente. O dicionário embutido (~3500 palavras)
+    // só preenche o espaço que sobrar até COMBINATOR_WORD_CAP — na ordem
+    // inversa, o dicionário embutido sozinho já estourava o cap e as
+    // custom_words nunca eram alcançadas.
+    let embedded_budget = COMBINATOR_WORD_CAP.saturating_sub(custom_words.len());
+    let wordlist: Vec<String> = custom_words
+        .iter()
+        .cloned()
+        .chain(
+            EMBEDDED_WORDLIST
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .take(embedded_budget),
+        )
+        .collect();
 
     let _ = progress_sink.add(CrackProgress {
         attempts: 0,
-        current_password: format!("Dicionário: testando {} senhas comuns...", total),
+        current_password: format!(
+            "Combinator: combinando {} palavras-base (profundidade {})...",
+            wordlist.len(),
+            depth
+        ),
         elapsed_seconds: 0,
         passwords_per_second: 0.0,
-        phase: "dictionary".to_string(),
+        phase: "combinator".to_string(),
+        stats: CrackStats::default(),
     });
 
-    let result = candidates
-        .par_iter()
-        .find_map_any(|pwd| {
-            if found_flag.load(Ordering::Relaxed) {
-                return None;
-            }
+    let found_flag = AtomicBool::new(false);
+    let candidates = combinator_candidates(&wordlist, depth, separators);
+
+    let mut batch: Vec<String> = Vec::with_capacity(COMBINATOR_BATCH_SIZE);
+    for candidate in candidates {
+        batch.push(candidate);
+        if batch.len() < COMBINATOR_BATCH_SIZE {
+            continue;
+        }
+
+        if let Some(pwd) = search_candidate_batch(
+            zip_data,
+            target_entry,
+            &batch,
+            &found_flag,
+            attempts,
+            phase_counters,
+            &phase_counters.combinator_attempts,
+        ) {
+            return Some(pwd);
+        }
+        batch.clear();
+    }
+
+    if !batch.is_empty() {
+        if let Some(pwd) = search_candidate_batch(
+            zip_data,
+            target_entry,
+            &batch,
+            &found_flag,
+            attempts,
+            phase_counters,
+            &phase_counters.combinator_attempts,
+        ) {
+            return Some(pwd);
+        }
+    }
+
+    None
+}
+
+/// Testa um lote de candidatos em paralelo contra o entry alvo,
+/// validando conteúdo via CRC32. Compartilhado entre `dictionary_attack`
+/// e `combinator_attack`; `phase_counter` recebe o total de tentativas
+/// do lote (cada chamador passa seu próprio contador de fase).
+fn search_candidate_batch(
+    zip_data: &[u8],
+    target_entry: usize,
+    batch: &[String],
+    found_flag: &AtomicBool,
+    attempts: &AtomicU64,
+    phase_counters: &PhaseCounters,
+    phase_counter: &AtomicU64,
+) -> Option<String> {
+    let batch_attempts = AtomicU64::new(0);
 
+    // Parseia o extra field AES (0x9901) uma única vez por lote, não por
+    // senha: entries ZipCrypto não pagam esse custo, e `by_index_decrypt`
+    // abaixo não entende AE-1/AE-2, então sem isso um ZIP WinZip AES
+    // simplesmente nunca batia em dictionary/combinator attack.
+    let aes_entry = read_raw_aes_entry(zip_data, target_entry);
+
+    let result = batch.par_iter().find_map_any(|pwd| {
+        if found_flag.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        batch_attempts.fetch_add(1, Ordering::Relaxed);
+        let pwd_bytes = pwd.as_bytes();
+
+        let result = if let Some((info, raw, expected_crc)) = &aes_entry {
+            try_unlock_aes_entry(raw, info, pwd_bytes, *expected_crc).map(|_| pwd.clone())
+        } else {
             let reader = Cursor::new(zip_data);
             let mut archive = match ZipArchive::new(reader) {
                 Ok(a) => a,
                 Err(_) => return None,
             };
 
-            dict_attempts.fetch_add(1, Ordering::Relaxed);
-
-            let pwd_bytes = pwd.as_bytes();
-            let result = match archive.by_index_decrypt(target_entry, pwd_bytes) {
+            match archive.by_index_decrypt(target_entry, pwd_bytes) {
                 Ok(Ok(mut file)) => {
                     let expected_crc = file.crc32();
                     let expected_size = file.size();
                     let mut buf = Vec::new();
-                    
+
                     match file.read_to_end(&mut buf) {
                         Ok(bytes_read) if bytes_read as u64 == expected_size => {
-                            // Validate CRC32 to eliminate false positives
                             let actual_crc = crc32fast::hash(&buf);
                             if actual_crc == expected_crc {
                                 Some(pwd.clone())
                             } else {
-                                None // CRC mismatch - false positive
+                                phase_counters.false_positive_count.fetch_add(1, Ordering::Relaxed);
+                                None
                             }
                         }
-                        _ => None, // Read failed or size mismatch
+                        _ => None,
                     }
                 }
                 _ => None,
-            };
-
-            if result.is_some() {
-                found_flag.store(true, Ordering::Relaxed);
             }
-            result
-        });
+        };
+
+        if result.is_some() {
+            found_flag.store(true, Ordering::Relaxed);
+        }
+        result
+    });
 
-    attempts.fetch_add(dict_attempts.load(Ordering::Relaxed), Ordering::Relaxed);
+    let total_batch_attempts = batch_attempts.load(Ordering::Relaxed);
+    attempts.fetch_add(total_batch_attempts, Ordering::Relaxed);
+    phase_counter.fetch_add(total_batch_attempts, Ordering::Relaxed);
     result
 }
 
 // ============================================================
-// MAIN ENTRY POINT
+// MARKOV MODEL — enumeração por probabilidade em vez de odômetro
+//
+// Treina um modelo de ordem 1 (frequência inicial + transições
+// caractere-a-caractere) na wordlist embutida, converte cada
+// probabilidade em um "nível" inteiro (0 = mais provável, 10 = menos
+// provável) e enumera candidatas por orçamento de nível crescente
+// L = 0, 1, 2, ... em vez de ordem lexicográfica. Isso visita
+// combinações prováveis (ex: "pass", "love") muito antes de combos
+// improváveis (ex: "zqxj") sem precisar ordenar o espaço inteiro.
 // ============================================================
 
-/// Quebra a senha de um arquivo ZIP usando força bruta paralela otimizada v2
-/// Fase 1: Dictionary attack (senhas comuns + mutações)
-/// Fase 2: Brute force incremental com chunks adaptativos
-pub fn crack_zip_password(
-    file_bytes: Vec<u8>,
-    config: CrackConfig,
-    progress_sink: StreamSink<CrackProgress>,
-) -> Result<()> {
-    let start_time = std::time::Instant::now();
+/// Nível máximo (menor probabilidade) atribuído a uma transição
+const MARKOV_MAX_LEVEL: u8 = 10;
 
-    let archive_len = ensure_valid_zip(&file_bytes, &progress_sink)?;
-    let target_entry =
-        match find_first_encrypted_entry(&file_bytes, archive_len, &progress_sink)? {
-            Some(idx) => idx,
-            None => return Ok(()),
-        };
+/// Fator de escala usado para converter -log2(p) em um nível inteiro
+const MARKOV_SCALE: f64 = 2.0;
 
-    // Charset compacto (stack-allocated, sem heap no hot path)
-    let charset = CompactCharset::new(&config);
-    if charset.is_empty() {
-        report_error(
-            &progress_sink,
-            "ERRO: Nenhum caractere selecionado".to_string(),
-        );
-        return Err(anyhow!("Nenhum caractere selecionado para teste"));
-    }
+/// Modelo de Markov de ordem 1 sobre um charset compacto (até 94 símbolos)
+struct MarkovModel {
+    /// Mapeia byte -> índice no charset ativo (255 = ausente)
+    charset_index: [u8; 256],
+    /// Nível de cada caractere como inicial de senha
+    initial_level: [u8; 94],
+    /// Nível de transição entre cada par de caracteres (from -> to)
+    transition_level: [[u8; 94]; 94],
+}
 
-    // Contadores atômicos compartilhados entre threads
-    let attempts = Arc::new(AtomicU64::new(0));
-    let found = Arc::new(AtomicBool::new(false));
-    let password_found = Arc::new(parking_lot::Mutex::new(None::<String>));
+impl MarkovModel {
+    /// Treina o modelo contando frequências iniciais e de transição
+    /// sobre `EMBEDDED_WORDLIST`, restrito aos caracteres do charset ativo.
+    fn train(charset: &[u8]) -> Self {
+        let mut charset_index = [255u8; 256];
+        for (i, &b) in charset.iter().enumerate() {
+            charset_index[b as usize] = i as u8;
+        }
 
-    // ── FASE 1: Dictionary Attack ──────────────────────────────
-    if config.use_dictionary {
-        if let Some(pwd) = dictionary_attack(
-            &file_bytes,
-            target_entry,
-            &config.custom_words,
-            &progress_sink,
-            &attempts,
-        ) {
-            found.store(true, Ordering::Relaxed);
-            *password_found.lock() = Some(pwd.clone());
+        let n = charset.len();
+        let mut initial_count = vec![0u64; n];
+        let mut transition_count = vec![vec![0u64; n]; n];
 
-            let elapsed_secs = start_time.elapsed().as_secs_f64();
-            let total_attempts = attempts.load(Ordering::Relaxed);
-            let rate = if elapsed_secs > 0.01 {
-                total_attempts as f64 / elapsed_secs
-            } else {
-                total_attempts as f64
-            };
-            let _ = progress_sink.add(CrackProgress {
-                attempts: total_attempts,
-                current_password: format!("FOUND:{}", pwd),
-                elapsed_seconds: elapsed_secs as u64,
-                passwords_per_second: rate,
-                phase: "dictionary".to_string(),
-            });
-            return Ok(());
+        for word in EMBEDDED_WORDLIST.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let bytes = word.as_bytes();
+            let mut prev_idx: Option<usize> = None;
+
+            for (pos, &b) in bytes.iter().enumerate() {
+                let idx = charset_index[b as usize];
+                if idx == 255 {
+                    prev_idx = None;
+                    continue;
+                }
+                let idx = idx as usize;
+
+                if pos == 0 {
+                    initial_count[idx] += 1;
+                }
+                if let Some(prev) = prev_idx {
+                    transition_count[prev][idx] += 1;
+                }
+                prev_idx = Some(idx);
+            }
         }
-    }
 
-    // ── FASE 2: Brute Force ────────────────────────────────────
-    let progress_thread = spawn_progress_thread(
-        Arc::clone(&attempts),
-        Arc::clone(&found),
-        progress_sink.clone(),
-    );
+        let initial_level = Self::counts_to_levels(&initial_count);
 
-    let zip_data: &[u8] = &file_bytes;
-    let charset_slice = charset.as_slice();
-    let chunk_size = adaptive_chunk_size(charset_slice.len(), config.max_length);
+        let mut transition_level = [[MARKOV_MAX_LEVEL; 94]; 94];
+        for (from, counts) in transition_count.iter().enumerate() {
+            let levels = Self::counts_to_levels(counts);
+            transition_level[from][..n].copy_from_slice(&levels);
+        }
 
-    for length in config.min_length..=config.max_length {
-        if found.load(Ordering::Relaxed) {
-            break;
+        let mut initial_level_arr = [MARKOV_MAX_LEVEL; 94];
+        initial_level_arr[..n].copy_from_slice(&initial_level);
+
+        Self {
+            charset_index,
+            initial_level: initial_level_arr,
+            transition_level,
         }
-        
-        // Wait if paused (allows pause/resume during password length transitions)
-        wait_if_paused();
+    }
 
-        let total = (charset_slice.len() as u64).saturating_pow(length as u32);
-        let num_chunks = (total + chunk_size - 1) / chunk_size;
+    /// Converte um vetor de contagens em níveis `round(-log2(p) / scale)`,
+    /// clampeados em `[0, MARKOV_MAX_LEVEL]`. Contagem zero vira o pior nível.
+    fn counts_to_levels(counts: &[u64]) -> Vec<u8> {
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return vec![MARKOV_MAX_LEVEL; counts.len()];
+        }
 
-        let result = (0..num_chunks).into_par_iter().find_map_any(|chunk_idx| {
+        counts
+            .iter()
+            .map(|&c| {
+                if c == 0 {
+                    return MARKOV_MAX_LEVEL;
+                }
+                let p = c as f64 / total as f64;
+                let level = (-p.log2() / MARKOV_SCALE).round();
+                (level.max(0.0) as u8).min(MARKOV_MAX_LEVEL)
+            })
+            .collect()
+    }
+
+    #[inline(always)]
+    fn level_of(&self, index: usize, is_initial: bool, prev_index: usize) -> u8 {
+        if is_initial {
+            self.initial_level[index]
+        } else {
+            self.transition_level[prev_index][index]
+        }
+    }
+}
+
+/// Tamanho do lote testado por vez durante a enumeração de um "anel" Markov —
+/// o anel inteiro (todas as senhas de `length` caracteres com um dado
+/// orçamento de nível) pode ser astronomicamente maior que `out`/`ring` jamais
+/// deveria crescer, então em vez de materializar o anel inteiro num único
+/// `Vec` ele é testado em lotes desse tamanho.
+const MARKOV_BATCH_SIZE: usize = 50_000;
+
+/// Enumera (via recursão) as senhas de `length` caracteres cujo somatório de
+/// níveis Markov é exatamente `budget`, chamando `on_candidate` para cada uma
+/// em vez de empilhar tudo num `Vec`. Mantém memória limitada pois não
+/// ordena o espaço inteiro — apenas visita o "anel" de nível constante
+/// `budget`, e quem chama decide quando/como consumir os candidatos
+/// (em lotes, via `on_candidate`). Para assim que `stop` sinalizar.
+fn markov_enumerate(
+    length: usize,
+    budget: u32,
+    charset: &[u8],
+    model: &MarkovModel,
+    buf: &mut Vec<u8>,
+    stop: &AtomicBool,
+    on_candidate: &mut dyn FnMut(&[u8]),
+) {
+    if stop.load(Ordering::Relaxed) {
+        return;
+    }
+
+    if buf.len() == length {
+        if budget == 0 {
+            on_candidate(buf);
+        }
+        return;
+    }
+
+    let is_initial = buf.is_empty();
+    let prev_index = if is_initial {
+        0
+    } else {
+        model.charset_index[*buf.last().unwrap() as usize] as usize
+    };
+
+    for (idx, &c) in charset.iter().enumerate() {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let level = model.level_of(idx, is_initial, prev_index) as u32;
+        if level > budget {
+            continue;
+        }
+
+        // A soma dos níveis precisa fechar exatamente em `budget`: poda
+        // caminhos em que nem o nível máximo em todas as posições restantes
+        // alcançaria o que sobra (`budget - level`).
+        let remaining_positions = length - buf.len() - 1;
+        if (remaining_positions as u32) * u32::from(MARKOV_MAX_LEVEL) < budget - level {
+            continue;
+        }
+
+        buf.push(c);
+        markov_enumerate(length, budget - level, charset, model, buf, stop, on_candidate);
+        buf.pop();
+    }
+}
+
+/// Busca por senha de `length` caracteres em ordem de probabilidade
+/// decrescente (orçamento de nível Markov crescente), em vez do odômetro puro.
+fn markov_brute_force(
+    zip_data: &[u8],
+    length: usize,
+    charset: &[u8],
+    model: &MarkovModel,
+    found: &AtomicBool,
+    attempts: &AtomicU64,
+) -> Option<String> {
+    let max_budget = (length as u32) * u32::from(MARKOV_MAX_LEVEL);
+
+    for budget in 0..=max_budget {
+        if found.load(Ordering::Relaxed) {
+            return None;
+        }
+        wait_if_paused();
+
+        let mut buf = Vec::with_capacity(length);
+        let mut batch: Vec<Vec<u8>> = Vec::with_capacity(MARKOV_BATCH_SIZE);
+        let mut result: Option<String> = None;
+
+        {
+            let mut on_candidate = |candidate: &[u8]| {
+                batch.push(candidate.to_vec());
+                if batch.len() < MARKOV_BATCH_SIZE {
+                    return;
+                }
+
+                let ring_attempts = AtomicU64::new(0);
+                let hit = batch.par_iter().find_map_any(|candidate| {
+                    if found.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    ring_attempts.fetch_add(1, Ordering::Relaxed);
+
+                    if try_unlock_ultra_safe(zip_data, candidate) {
+                        found.store(true, Ordering::Relaxed);
+                        return Some(String::from_utf8_lossy(candidate).into_owned());
+                    }
+                    None
+                });
+
+                attempts.fetch_add(ring_attempts.load(Ordering::Relaxed), Ordering::Relaxed);
+                batch.clear();
+
+                if hit.is_some() {
+                    result = hit;
+                }
+            };
+
+            markov_enumerate(length, budget, charset, model, &mut buf, found, &mut on_candidate);
+        }
+
+        // Último lote incompleto (menor que MARKOV_BATCH_SIZE) que sobrou
+        // da enumeração.
+        if result.is_none() && !batch.is_empty() && !found.load(Ordering::Relaxed) {
+            let ring_attempts = AtomicU64::new(0);
+            let hit = batch.par_iter().find_map_any(|candidate| {
+                if found.load(Ordering::Relaxed) {
+                    return None;
+                }
+                ring_attempts.fetch_add(1, Ordering::Relaxed);
+
+                if try_unlock_ultra_safe(zip_data, candidate) {
+                    found.store(true, Ordering::Relaxed);
+                    return Some(String::from_utf8_lossy(candidate).into_owned());
+                }
+                None
+            });
+
+            attempts.fetch_add(ring_attempts.load(Ordering::Relaxed), Ordering::Relaxed);
+            result = hit;
+        }
+
+        if result.is_some() {
+            return result;
+        }
+    }
+
+    None
+}
+
+// ============================================================
+// MASK ATTACK — charset por posição (odômetro de base mista)
+//
+// Quando o usuário já conhece a estrutura da senha (ex: 4 letras
+// maiúsculas + 4 dígitos), uma máscara evita o cross-product do
+// charset uniforme. Cada posição carrega seu próprio charset, então
+// o espaço total é o produto dos tamanhos por posição em vez de
+// `charset_size.pow(length)`.
+// ============================================================
+
+/// Máscara com um charset independente por posição, parseada de
+/// tokens `?l` `?u` `?d` `?s` (classe) ou caracteres literais.
+#[derive(Debug, Clone)]
+struct Mask {
+    positions: Vec<Vec<u8>>,
+}
+
+impl Mask {
+    /// Parseia a string de máscara em um charset por posição
+    fn parse(spec: &str) -> Result<Self> {
+        let chars: Vec<char> = spec.chars().collect();
+        let mut positions = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] == '?' && i + 1 < chars.len() {
+                let class: Vec<u8> = match chars[i + 1] {
+                    'l' => (b'a'..=b'z').collect(),
+                    'u' => (b'A'..=b'Z').collect(),
+                    'd' => (b'0'..=b'9').collect(),
+                    's' => b"!@#$%^&*()-_=+[]{}|;:'\",.<>?/~`\\".to_vec(),
+                    other => return Err(anyhow!("Token de máscara desconhecido: ?{}", other)),
+                };
+                positions.push(class);
+                i += 2;
+            } else {
+                // Caractere literal: posição com charset de tamanho 1 (uma
+                // posição por byte UTF-8 do caractere)
+                let mut buf = [0u8; 4];
+                for &b in chars[i].encode_utf8(&mut buf).as_bytes() {
+                    positions.push(vec![b]);
+                }
+                i += 1;
+            }
+        }
+
+        if positions.is_empty() {
+            return Err(anyhow!("Máscara vazia"));
+        }
+
+        Ok(Self { positions })
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Número total de combinações (produto dos tamanhos por posição)
+    fn total_combinations(&self) -> u64 {
+        self.positions
+            .iter()
+            .fold(1u64, |acc, class| acc.saturating_mul(class.len() as u64))
+    }
+}
+
+/// Gera a senha em bytes para `index`, igual a `index_to_bytes` mas com
+/// um charset diferente em cada posição (odômetro de base mista)
+#[inline(always)]
+fn index_to_bytes_mask(mut index: u64, mask: &Mask, buf: &mut [u8]) {
+    for i in (0..mask.len()).rev() {
+        let class = &mask.positions[i];
+        let base = class.len() as u64;
+        buf[i] = class[(index % base) as usize];
+        index /= base;
+    }
+}
+
+/// Incrementa o buffer como um odômetro de base mista: cada posição
+/// usa seu próprio charset ao fazer carry para a posição anterior
+#[inline(always)]
+fn increment_mask(buf: &mut [u8], mask: &Mask) {
+    for i in (0..mask.len()).rev() {
+        let class = &mask.positions[i];
+        let pos = class.iter().position(|&c| c == buf[i]).unwrap_or(0);
+        if pos + 1 < class.len() {
+            buf[i] = class[pos + 1];
+            return;
+        }
+        buf[i] = class[0];
+        // carry para a posição anterior
+    }
+}
+
+/// Executa a Fase 2 sobre uma máscara em vez do `min_length..=max_length`
+/// uniforme, reaproveitando o mesmo chunking paralelo e a validação
+/// `try_unlock_ultra_safe` do brute force padrão.
+fn mask_brute_force(
+    zip_data: &[u8],
+    mask: &Mask,
+    found: &AtomicBool,
+    attempts: &AtomicU64,
+    start_chunk: u64,
+    max_chunk_done: Option<&AtomicU64>,
+) -> Option<String> {
+    let total = mask.total_combinations();
+    let avg_charset_len = mask.positions.iter().map(|c| c.len()).sum::<usize>() / mask.len().max(1);
+    let chunk_size = adaptive_chunk_size(avg_charset_len, mask.len());
+    let num_chunks = (total + chunk_size - 1) / chunk_size;
+
+    (start_chunk..num_chunks).into_par_iter().find_map_any(|chunk_idx| {
+        if found.load(Ordering::Relaxed) {
+            return None;
+        }
+        wait_if_paused();
+
+        let start_idx = chunk_idx * chunk_size;
+        let end_idx = (start_idx + chunk_size).min(total);
+
+        let mut pwd_buf = vec![0u8; mask.len()];
+        let mut local_count = 0u64;
+        index_to_bytes_mask(start_idx, mask, &mut pwd_buf);
+
+        for _index in start_idx..end_idx {
+            if local_count & 0x1FF == 0 && local_count > 0 {
+                if found.load(Ordering::Relaxed) {
+                    break;
+                }
+                wait_if_paused();
+            }
+
+            if try_unlock_ultra_safe(zip_data, &pwd_buf) {
+                found.store(true, Ordering::Relaxed);
+                attempts.fetch_add(local_count + 1, Ordering::Relaxed);
+                return Some(String::from_utf8_lossy(&pwd_buf).into_owned());
+            }
+
+            local_count += 1;
+            if local_count & 0x7FF == 0 {
+                attempts.fetch_add(2048, Ordering::Relaxed);
+                local_count -= 2048;
+            }
+
+            increment_mask(&mut pwd_buf, mask);
+        }
+
+        if local_count > 0 {
+            attempts.fetch_add(local_count, Ordering::Relaxed);
+        }
+
+        // Marca este chunk como concluído para o checkpoint (aproximado:
+        // como os workers rodam em paralelo, o maior índice concluído
+        // não garante que todos os menores também terminaram)
+        if let Some(counter) = max_chunk_done {
+            counter.fetch_max(chunk_idx, Ordering::Relaxed);
+        }
+
+        None
+    })
+}
+
+// ============================================================
+// CHECKPOINT / RESUME
+//
+// Permite que runs longos de brute force sobrevivam a um restart do
+// app: a cada `checkpoint_interval_secs`, o progresso atual (length,
+// índice de chunk, attempts, tempo decorrido) é serializado num
+// arquivo simples `chave=valor`. No próximo start, se o arquivo
+// existir e o hash do ZIP alvo bater, a Fase 2 retoma desse ponto em
+// vez de recomeçar em `config.min_length`.
+//
+// Granularidade do resume: só o `length` é usado para decidir de onde
+// retomar. `chunk_index` é o maior chunk que algum worker terminou,
+// mas como os chunks são distribuídos por work-stealing (`find_map_any`)
+// não há garantia de que chunks menores já tenham terminado quando um
+// maior termina primeiro — tratá-lo como ponto de resume seguro pularia
+// candidatas nunca testadas. Por isso ele é só informativo (salvo no
+// arquivo para diagnóstico) e o `length` retomado sempre reinicia do
+// chunk 0, o que é mais lento mas comprovadamente exaustivo.
+// ============================================================
+
+/// Estado persistido de uma execução da Fase 2
+#[derive(Debug, Clone)]
+struct CrackCheckpoint {
+    length: usize,
+    /// Maior chunk concluído por algum worker no momento do save — só
+    /// para diagnóstico/telemetria, não é usado como ponto de resume
+    /// (ver comentário da seção acima)
+    chunk_index: u64,
+    attempts: u64,
+    elapsed_seconds: u64,
+    /// CRC32 do `file_bytes` alvo, usado para validar que o checkpoint
+    /// pertence a este mesmo arquivo antes de retomar
+    file_hash: u32,
+}
+
+impl CrackCheckpoint {
+    fn serialize(&self) -> String {
+        format!(
+            "length={}\nchunk_index={}\nattempts={}\nelapsed_seconds={}\nfile_hash={:08x}\n",
+            self.length, self.chunk_index, self.attempts, self.elapsed_seconds, self.file_hash
+        )
+    }
+
+    fn parse(data: &str) -> Result<Self> {
+        let mut length = None;
+        let mut chunk_index = None;
+        let mut attempts = None;
+        let mut elapsed_seconds = None;
+        let mut file_hash = None;
+
+        for line in data.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                match key {
+                    "length" => length = value.parse().ok(),
+                    "chunk_index" => chunk_index = value.parse().ok(),
+                    "attempts" => attempts = value.parse().ok(),
+                    "elapsed_seconds" => elapsed_seconds = value.parse().ok(),
+                    "file_hash" => file_hash = u32::from_str_radix(value, 16).ok(),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Self {
+            length: length.ok_or_else(|| anyhow!("checkpoint inválido: 'length' ausente"))?,
+            chunk_index: chunk_index.unwrap_or(0),
+            attempts: attempts.unwrap_or(0),
+            elapsed_seconds: elapsed_seconds.unwrap_or(0),
+            file_hash: file_hash.ok_or_else(|| anyhow!("checkpoint inválido: 'file_hash' ausente"))?,
+        })
+    }
+}
+
+/// Salva um checkpoint no caminho fornecido pelo caller
+fn save_checkpoint(path: &str, checkpoint: &CrackCheckpoint) -> Result<()> {
+    std::fs::write(path, checkpoint.serialize())
+        .map_err(|e| anyhow!("Falha ao salvar checkpoint em '{}': {}", path, e))
+}
+
+/// Carrega um checkpoint do caminho fornecido pelo caller
+fn load_checkpoint(path: &str) -> Result<CrackCheckpoint> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Falha ao ler checkpoint de '{}': {}", path, e))?;
+    CrackCheckpoint::parse(&data)
+}
+
+// ============================================================
+// MAIN ENTRY POINT
+// ============================================================
+
+/// Quebra a senha de um arquivo ZIP usando força bruta paralela otimizada v2
+/// Fase 1: Dictionary attack (senhas comuns + mutações)
+/// Fase 2: Brute force incremental com chunks adaptativos
+pub fn crack_zip_password(
+    file_bytes: Vec<u8>,
+    config: CrackConfig,
+    progress_sink: StreamSink<CrackProgress>,
+) -> Result<()> {
+    let file_hash = crc32fast::hash(&file_bytes);
+
+    // Tenta retomar de um checkpoint salvo: só é válido se o hash do
+    // arquivo alvo bater com o que gerou o checkpoint.
+    let resume = config.checkpoint_path.as_ref().and_then(|path| match load_checkpoint(path) {
+        Ok(cp) if cp.file_hash == file_hash => Some(cp),
+        Ok(_) => {
+            report_error(
+                &progress_sink,
+                "Checkpoint encontrado não corresponde a este arquivo, ignorando".to_string(),
+            );
+            None
+        }
+        Err(_) => None,
+    });
+
+    // Desloca o instante de início para trás pelo tempo já decorrido,
+    // assim `elapsed_seconds` reportado continua contando a partir do
+    // início da busca original em vez de zerar ao retomar.
+    let start_time = resume
+        .as_ref()
+        .and_then(|cp| std::time::Instant::now().checked_sub(std::time::Duration::from_secs(cp.elapsed_seconds)))
+        .unwrap_or_else(std::time::Instant::now);
+
+    let archive_len = ensure_valid_zip(&file_bytes, &progress_sink)?;
+    let target_entry =
+        match find_first_encrypted_entry(&file_bytes, archive_len, &progress_sink)? {
+            Some(idx) => idx,
+            None => return Ok(()),
+        };
+
+    // Charset compacto (stack-allocated, sem heap no hot path)
+    // Quando uma máscara é fornecida ela substitui o charset uniforme,
+    // então só validamos o charset global se não houver máscara.
+    let charset = CompactCharset::new(&config);
+    let mask: Option<Mask> = match &config.mask {
+        Some(spec) => Some(Mask::parse(spec)?),
+        None => None,
+    };
+
+    if mask.is_none() && charset.is_empty() {
+        report_error(
+            &progress_sink,
+            "ERRO: Nenhum caractere selecionado".to_string(),
+        );
+        return Err(anyhow!("Nenhum caractere selecionado para teste"));
+    }
+
+    // Contadores atômicos compartilhados entre threads
+    let attempts = Arc::new(AtomicU64::new(resume.as_ref().map(|cp| cp.attempts).unwrap_or(0)));
+    let found = Arc::new(AtomicBool::new(false));
+    // Travado e zerado no Drop (ver LockedBuffer) — a senha encontrada é o
+    // segredo mais sensível de toda a busca, então fica aqui em vez de em
+    // uma String comum até o instante em que precisa virar String para
+    // sair pela fronteira do FFI (report_found/CrackProgress)
+    let password_found = Arc::new(parking_lot::Mutex::new(None::<LockedBuffer>));
+    let phase_counters = Arc::new(PhaseCounters::new());
+
+    // ── FASE 1: Dictionary Attack (pulada ao retomar um checkpoint) ─
+    if resume.is_none() && config.use_dictionary {
+        let rules = MutationRules::parse(&config.rules);
+        if let Some(pwd) = dictionary_attack(
+            &file_bytes,
+            target_entry,
+            &config.custom_words,
+            &rules,
+            &progress_sink,
+            &attempts,
+            &phase_counters,
+        ) {
+            found.store(true, Ordering::Relaxed);
+            report_found(&progress_sink, &pwd, "dictionary", start_time, &attempts, &phase_counters);
+            return Ok(());
+        }
+    }
+
+    // ── FASE 1.5: Combinator Attack (pulada ao retomar um checkpoint) ─
+    if resume.is_none() && config.combinator_depth >= 2 {
+        if let Some(pwd) = combinator_attack(
+            &file_bytes,
+            target_entry,
+            &config.custom_words,
+            config.combinator_depth,
+            &config.separators,
+            &progress_sink,
+            &attempts,
+            &phase_counters,
+        ) {
+            found.store(true, Ordering::Relaxed);
+            report_found(&progress_sink, &pwd, "combinator", start_time, &attempts, &phase_counters);
+            return Ok(());
+        }
+    }
+
+    // ── FASE 2: Brute Force ────────────────────────────────────
+    let effective_min_length = resume.as_ref().map(|cp| cp.length).unwrap_or(config.min_length);
+    // O resume só granula por `length` (ver comentário da seção de
+    // CHECKPOINT / RESUME) — o comprimento retomado sempre reinicia do
+    // chunk 0, então não há um `resume_chunk_index` real a propagar.
+
+    let total_keyspace = match &mask {
+        Some(mask) => mask.total_combinations(),
+        None => {
+            let charset_size = charset.as_slice().len() as u64;
+            (config.min_length..=config.max_length)
+                .fold(0u64, |acc, len| acc.saturating_add(charset_size.saturating_pow(len as u32)))
+        }
+    };
+
+    let checkpoint_current_length = Arc::new(AtomicUsize::new(effective_min_length));
+    // Telemetria apenas (ver comentário da seção CHECKPOINT / RESUME);
+    // o length loop zera isso de novo antes do primeiro chunk
+    let checkpoint_max_chunk = Arc::new(AtomicU64::new(0));
+    let checkpoint_ctx = config.checkpoint_path.as_ref().map(|path| CheckpointContext {
+        path: path.clone(),
+        interval_secs: config.checkpoint_interval_secs,
+        file_hash,
+        current_length: Arc::clone(&checkpoint_current_length),
+        max_chunk_done: Arc::clone(&checkpoint_max_chunk),
+    });
+
+    let progress_thread = spawn_progress_thread(
+        Arc::clone(&attempts),
+        Arc::clone(&found),
+        progress_sink.clone(),
+        Arc::clone(&phase_counters),
+        total_keyspace,
+        checkpoint_ctx,
+        start_time,
+    );
+
+    let zip_data: &[u8] = &file_bytes;
+
+    // Máscara substitui o laço min_length..=max_length inteiro
+    if let Some(mask) = &mask {
+        let result = mask_brute_force(
+            zip_data,
+            mask,
+            &found,
+            &attempts,
+            0,
+            Some(&checkpoint_max_chunk),
+        );
+        if let Some(password) = result {
+            // Materializada pelo próprio mask_brute_force como String — trava
+            // e zera o quanto antes em vez de deixá-la solta até o report
+            *password_found.lock() = Some(LockedBuffer::new(password.into_bytes()));
+        }
+
+        found.store(true, Ordering::Relaxed);
+        let _ = progress_thread.join();
+
+        let elapsed_secs = start_time.elapsed().as_secs_f64();
+        let total_attempts = attempts.load(Ordering::Relaxed);
+        // take() em vez de clone(): materializa a String final de saída uma
+        // única vez, bem na fronteira do report, e derruba o LockedBuffer
+        // (zerando-o) logo em seguida
+        let password = password_found.lock().take().map(|buf| String::from_utf8_lossy(buf.as_slice()).into_owned());
+        let rate = if elapsed_secs > 0.1 {
+            total_attempts as f64 / elapsed_secs
+        } else {
+            0.0
+        };
+        let keyspace_percent = if total_keyspace > 0 {
+            (total_attempts as f64 / total_keyspace as f64 * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        let _ = progress_sink.add(CrackProgress {
+            attempts: total_attempts,
+            current_password: password
+                .as_deref()
+                .map(|p| format!("FOUND:{}", p))
+                .unwrap_or_default(),
+            elapsed_seconds: elapsed_secs as u64,
+            passwords_per_second: rate,
+            phase: "bruteforce".to_string(),
+            stats: phase_counters.to_stats(total_attempts, keyspace_percent, Some(0)),
+        });
+
+        return Ok(());
+    }
+
+    let charset_slice = charset.as_slice();
+    let chunk_size = adaptive_chunk_size(charset_slice.len(), config.max_length);
+    let markov_model = if config.markov {
+        Some(MarkovModel::train(charset_slice))
+    } else {
+        None
+    };
+
+    for length in effective_min_length..=config.max_length {
+        if found.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // Wait if paused (allows pause/resume during password length transitions)
+        wait_if_paused();
+
+        checkpoint_current_length.store(length, Ordering::Relaxed);
+        // checkpoint_max_chunk é telemetria de uma run anterior (possivelmente
+        // de outro length, com outra numeração de chunks) — zera antes de
+        // começar este length para não herdar um valor que não significa nada
+        // aqui, e nunca é usado como ponto de resume (sempre recomeça do 0)
+        checkpoint_max_chunk.store(0, Ordering::Relaxed);
+        let start_chunk = 0u64;
+
+        if let Some(model) = &markov_model {
+            let result = markov_brute_force(zip_data, length, charset_slice, model, &found, &attempts);
+            if let Some(password) = result {
+                *password_found.lock() = Some(LockedBuffer::new(password.into_bytes()));
+                break;
+            }
+            continue;
+        }
+
+        let total = (charset_slice.len() as u64).saturating_pow(length as u32);
+        let num_chunks = (total + chunk_size - 1) / chunk_size;
+
+        let result = (start_chunk..num_chunks).into_par_iter().find_map_any(|chunk_idx| {
             if found.load(Ordering::Relaxed) {
                 return None;
             }
-            
+
             // Check pause flag in parallel threads
             wait_if_paused();
 
@@ -427,11 +1669,13 @@ pub fn crack_zip_password(
             let end_idx = (start_idx + chunk_size).min(total);
 
             // Buffers reutilizáveis (zero alocação no loop)
-            let mut pwd_buf = vec![0u8; length];
+            // Travado em memória e zerado no Drop: a senha candidata nunca
+            // deve sobreviver em um core dump ou ser paginada para o swap
+            let mut pwd_buf = LockedBuffer::new(vec![0u8; length]);
             let mut local_count = 0u64;
 
             // Inicializa pwd_buf para start_idx (primeira senha do chunk)
-            index_to_bytes(start_idx, charset_slice, &mut pwd_buf);
+            index_to_bytes(start_idx, charset_slice, pwd_buf.inner_mut());
 
             for _index in start_idx..end_idx {
                 // Checa found flag a cada 512 tentativas (branch-prediction friendly)
@@ -443,10 +1687,14 @@ pub fn crack_zip_password(
                 }
 
                 // ULTRA SAFE TEST (creates fresh archive, tests all entries)
-                if try_unlock_ultra_safe(zip_data, &pwd_buf) {
+                if try_unlock_ultra_safe(zip_data, pwd_buf.as_slice()) {
                     found.store(true, Ordering::Relaxed);
                     attempts.fetch_add(local_count + 1, Ordering::Relaxed);
-                    return Some(String::from_utf8_lossy(&pwd_buf).into_owned());
+                    // Devolve o próprio LockedBuffer em vez de materializar uma
+                    // String solta aqui — a senha encontrada é o segredo que
+                    // mais importa proteger, e uma String comum não é travada
+                    // nem zerada no drop
+                    return Some(pwd_buf);
                 }
 
                 local_count += 1;
@@ -459,7 +1707,7 @@ pub fn crack_zip_password(
 
                 // Incremento de "odômetro" — avança pwd_buf para a próxima senha
                 // Muito mais rápido que recalcular via divisões a cada iteração
-                increment_password(&mut pwd_buf, charset_slice);
+                increment_password(pwd_buf.inner_mut(), charset_slice);
             }
 
             // Flush do restante
@@ -467,6 +1715,9 @@ pub fn crack_zip_password(
                 attempts.fetch_add(local_count, Ordering::Relaxed);
             }
 
+            // Marca o chunk como concluído para o próximo checkpoint periódico
+            checkpoint_max_chunk.fetch_max(chunk_idx, Ordering::Relaxed);
+
             None
         });
 
@@ -482,31 +1733,127 @@ pub fn crack_zip_password(
 
     let elapsed_secs = start_time.elapsed().as_secs_f64();
     let total_attempts = attempts.load(Ordering::Relaxed);
-    let password = password_found.lock().clone();
+    // take() materializa a String final de saída uma única vez, na
+    // fronteira do report, e derruba o LockedBuffer (zerando-o) em seguida
+    let password = password_found.lock().take().map(|buf| String::from_utf8_lossy(buf.as_slice()).into_owned());
 
     let rate = if elapsed_secs > 0.1 {
         total_attempts as f64 / elapsed_secs
     } else {
         0.0
     };
+    let keyspace_percent = if total_keyspace > 0 {
+        (total_attempts as f64 / total_keyspace as f64 * 100.0).min(100.0)
+    } else {
+        0.0
+    };
+
+    let _ = progress_sink.add(CrackProgress {
+        attempts: total_attempts,
+        current_password: password
+            .as_deref()
+            .map(|p| format!("FOUND:{}", p))
+            .unwrap_or_default(),
+        elapsed_seconds: elapsed_secs as u64,
+        passwords_per_second: rate,
+        phase: "bruteforce".to_string(),
+        stats: phase_counters.to_stats(total_attempts, keyspace_percent, Some(0)),
+    });
+
+    Ok(())
+}
+
+// ============================================================
+// HOT PATH FUNCTIONS (chamadas milhões de vezes)
+// ============================================================
 
+/// Reporta que uma senha foi encontrada, na fase `phase` informada pelo chamador
+fn report_found(
+    progress_sink: &StreamSink<CrackProgress>,
+    password: &str,
+    phase: &str,
+    start_time: std::time::Instant,
+    attempts: &AtomicU64,
+    phase_counters: &PhaseCounters,
+) {
+    let elapsed_secs = start_time.elapsed().as_secs_f64();
+    let total_attempts = attempts.load(Ordering::Relaxed);
+    let rate = if elapsed_secs > 0.01 {
+        total_attempts as f64 / elapsed_secs
+    } else {
+        total_attempts as f64
+    };
     let _ = progress_sink.add(CrackProgress {
         attempts: total_attempts,
-        current_password: password
-            .as_deref()
-            .map(|p| format!("FOUND:{}", p))
-            .unwrap_or_default(),
+        current_password: format!("FOUND:{}", password),
         elapsed_seconds: elapsed_secs as u64,
         passwords_per_second: rate,
-        phase: "bruteforce".to_string(),
+        phase: phase.to_string(),
+        stats: phase_counters.to_stats(total_attempts, 100.0, Some(0)),
     });
+}
 
-    Ok(())
+/// Campos de `CrackConfig` que `crack_zip_password_from_path` e
+/// `crack_zip_password_worker_pool` não implementam — essas variantes só
+/// cobrem dicionário + força bruta por odômetro, não o pipeline
+/// combinator/Markov/máscara/checkpoint de `crack_zip_password`.
+/// `supports_rules` existe porque `crack_zip_password_from_path` passou a
+/// honrar `rules` na fase de dicionário, mas `crack_zip_password_worker_pool`
+/// (puramente força bruta, sem fase de dicionário nenhuma) ainda não tem
+/// onde aplicá-las. `supports_dictionary` é por isso mesmo: sem fase de
+/// dicionário, `use_dictionary`/`custom_words` (o default de `CrackConfig`
+/// já deixa `use_dictionary: true`) não têm efeito nenhum em
+/// `crack_zip_password_worker_pool`.
+fn unsupported_config_fields(config: &CrackConfig, supports_rules: bool, supports_dictionary: bool) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if !supports_dictionary {
+        if config.use_dictionary {
+            fields.push("use_dictionary");
+        }
+        if !config.custom_words.is_empty() {
+            fields.push("custom_words");
+        }
+    }
+    if !supports_rules && !config.rules.is_empty() {
+        fields.push("rules");
+    }
+    if config.combinator_depth > 1 {
+        fields.push("combinator_depth");
+    }
+    if config.markov {
+        fields.push("markov");
+    }
+    if config.mask.is_some() {
+        fields.push("mask");
+    }
+    if config.checkpoint_path.is_some() {
+        fields.push("checkpoint_path");
+    }
+    fields
 }
 
-// ============================================================
-// HOT PATH FUNCTIONS (chamadas milhões de vezes)
-// ============================================================
+/// Valida que `config` não usa campos fora do que este engine implementa;
+/// reporta erro via `progress_sink` e retorna `Err` em vez de simplesmente
+/// ignorar o campo em silêncio (o que faria o usuário achar que, por
+/// exemplo, a máscara configurada estava em uso quando não estava).
+fn reject_unsupported_config(
+    config: &CrackConfig,
+    supports_rules: bool,
+    supports_dictionary: bool,
+    progress_sink: &StreamSink<CrackProgress>,
+) -> Result<()> {
+    let unsupported = unsupported_config_fields(config, supports_rules, supports_dictionary);
+    if unsupported.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "ERRO: este modo de quebra não suporta: {} (use crack_zip_password para esses recursos)",
+        unsupported.join(", ")
+    );
+    report_error(progress_sink, message.clone());
+    Err(anyhow!(message))
+}
 
 /// Reporta erro via progresso para a UI
 fn report_error(progress_sink: &StreamSink<CrackProgress>, message: String) {
@@ -516,6 +1863,7 @@ fn report_error(progress_sink: &StreamSink<CrackProgress>, message: String) {
         elapsed_seconds: 0,
         passwords_per_second: 0.0,
         phase: "error".to_string(),
+        stats: CrackStats::default(),
     });
 }
 
@@ -526,6 +1874,7 @@ fn report_progress(
     current_password: String,
     elapsed_seconds: u64,
     passwords_per_second: f64,
+    stats: CrackStats,
 ) {
     let _ = progress_sink.add(CrackProgress {
         attempts,
@@ -533,6 +1882,7 @@ fn report_progress(
         elapsed_seconds,
         passwords_per_second,
         phase: "bruteforce".to_string(),
+        stats,
     });
 }
 
@@ -582,84 +1932,669 @@ fn find_first_encrypted_entry(
         }
     }
 
-    report_progress(progress_sink, 0, "ZIP não possui arquivos criptografados".to_string(), 0, 0.0);
-    Ok(None)
-}
+    report_progress(
+        progress_sink,
+        0,
+        "ZIP não possui arquivos criptografados".to_string(),
+        0,
+        0.0,
+        CrackStats::default(),
+    );
+    Ok(None)
+}
+
+/// Thread de progresso (200ms para reduzir overhead)
+/// `total_keyspace` alimenta o percentual de keyspace e o ETA em `CrackStats`;
+/// `start` é o instante de início da busca (já deslocado ao retomar)
+fn spawn_progress_thread(
+    attempts: Arc<AtomicU64>,
+    found: Arc<AtomicBool>,
+    progress_sink: StreamSink<CrackProgress>,
+    phase_counters: Arc<PhaseCounters>,
+    total_keyspace: u64,
+    checkpoint: Option<CheckpointContext>,
+    start: std::time::Instant,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut last_checkpoint_save = std::time::Instant::now();
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+
+            if found.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let current_attempts = attempts.load(Ordering::Relaxed);
+            let elapsed_secs = start.elapsed().as_secs_f64();
+
+            // Report 0 speed when paused
+            let rate = if is_paused() {
+                0.0
+            } else if elapsed_secs > 0.1 {
+                current_attempts as f64 / elapsed_secs
+            } else {
+                0.0
+            };
+
+            let keyspace_percent = if total_keyspace > 0 {
+                (current_attempts as f64 / total_keyspace as f64 * 100.0).min(100.0)
+            } else {
+                0.0
+            };
+            let eta_seconds = if rate > 0.1 && total_keyspace > current_attempts {
+                Some(((total_keyspace - current_attempts) as f64 / rate) as u64)
+            } else {
+                None
+            };
+
+            report_progress(
+                &progress_sink,
+                current_attempts,
+                String::from("..."),
+                elapsed_secs as u64,
+                rate,
+                phase_counters.to_stats(current_attempts, keyspace_percent, eta_seconds),
+            );
+
+            if let Some(ctx) = &checkpoint {
+                if last_checkpoint_save.elapsed().as_secs() >= ctx.interval_secs {
+                    let snapshot = CrackCheckpoint {
+                        length: ctx.current_length.load(Ordering::Relaxed),
+                        chunk_index: ctx.max_chunk_done.load(Ordering::Relaxed),
+                        attempts: current_attempts,
+                        elapsed_seconds: elapsed_secs as u64,
+                        file_hash: ctx.file_hash,
+                    };
+                    let _ = save_checkpoint(&ctx.path, &snapshot);
+                    last_checkpoint_save = std::time::Instant::now();
+                }
+            }
+        }
+    })
+}
+
+/// Contexto compartilhado com a thread de progresso para salvar
+/// checkpoints periódicos sem bloquear o laço de brute force
+struct CheckpointContext {
+    path: String,
+    interval_secs: u64,
+    file_hash: u32,
+    current_length: Arc<AtomicUsize>,
+    max_chunk_done: Arc<AtomicU64>,
+}
+
+/// Gera senha em bytes direto no buffer (zero alocação)
+/// Usado apenas para INICIALIZAR o buffer no começo de cada chunk.
+/// Dentro do loop, usamos increment_password() que é muito mais rápido.
+#[inline(always)]
+fn index_to_bytes(mut index: u64, charset: &[u8], buf: &mut [u8]) {
+    let base = charset.len() as u64;
+    for i in (0..buf.len()).rev() {
+        buf[i] = charset[(index % base) as usize];
+        index /= base;
+    }
+}
+
+/// Incrementa o buffer de senha como um odômetro.
+/// Muito mais rápido que index_to_bytes() porque:
+/// - 99%+ das vezes só muda o último byte (1 operação)
+/// - Apenas faz carry quando atinge o fim do charset
+/// - Zero divisões, zero multiplicações
+/// - Perfeitamente predizível pelo branch predictor da CPU
+#[inline(always)]
+fn increment_password(buf: &mut [u8], charset: &[u8]) {
+    let last_char = charset[charset.len() - 1];
+    // Percorre do último byte para o primeiro (como somar 1 num número)
+    for i in (0..buf.len()).rev() {
+        if buf[i] == last_char {
+            // Carry: volta para o primeiro char e continua
+            buf[i] = charset[0];
+        } else {
+            // Encontra o próximo char no charset e para
+            // Usa busca linear (charset é pequeno, cabe no L1 cache)
+            let pos = charset.iter().position(|&c| c == buf[i]).unwrap_or(0);
+            buf[i] = charset[pos + 1];
+            return;
+        }
+    }
+    // Overflow total (todas as posições fizeram carry) — não deve acontecer
+    // porque o loop externo controla o range
+}
+
+// ============================================================
+// WINZIP AES (AE-1/AE-2) — extra field 0x9901, compression method 99
+// `by_index_decrypt` só entende ZipCrypto clássico; entries AES
+// precisam de parsing manual do extra field + PBKDF2-HMAC-SHA1 +
+// AES-CTR, detectados à parte antes de cada caminho ZipCrypto.
+// ============================================================
+
+/// Força declarada no extra field 0x9901 (1=128, 2=192, 3=256 bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AesStrength {
+    Aes128,
+    Aes192,
+    Aes256,
+}
+
+impl AesStrength {
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(AesStrength::Aes128),
+            2 => Some(AesStrength::Aes192),
+            3 => Some(AesStrength::Aes256),
+            _ => None,
+        }
+    }
+
+    /// Tamanho do salt (8/12/16 bytes para 128/192/256 bits)
+    fn salt_len(self) -> usize {
+        match self {
+            AesStrength::Aes128 => 8,
+            AesStrength::Aes192 => 12,
+            AesStrength::Aes256 => 16,
+        }
+    }
+
+    /// Tamanho de cada chave derivada (criptografia e autenticação têm
+    /// o mesmo tamanho; PBKDF2 deriva `2*key_len + 2` bytes no total)
+    fn key_len(self) -> usize {
+        match self {
+            AesStrength::Aes128 => 16,
+            AesStrength::Aes192 => 24,
+            AesStrength::Aes256 => 32,
+        }
+    }
+}
+
+/// Metadados do extra field 0x9901 de um entry WinZip AES.
+/// `ae2` distingue AE-1 (mantém o CRC32 original) de AE-2 (zera o CRC32
+/// e delega a integridade à tag HMAC-SHA1 no final do payload).
+struct AesEntryInfo {
+    strength: AesStrength,
+    ae2: bool,
+}
+
+/// Percorre os extra fields locais em busca do header 0x9901 (WinZip AES)
+fn parse_aes_extra_field(extra: &[u8]) -> Option<AesEntryInfo> {
+    let mut cursor = extra;
+    while cursor.len() >= 4 {
+        let header_id = u16::from_le_bytes([cursor[0], cursor[1]]);
+        let data_size = u16::from_le_bytes([cursor[2], cursor[3]]) as usize;
+        if cursor.len() < 4 + data_size {
+            return None;
+        }
+        let data = &cursor[4..4 + data_size];
+
+        if header_id == 0x9901 && data_size >= 7 {
+            let vendor_version = u16::from_le_bytes([data[0], data[1]]);
+            // data[2..4] é sempre "AE" (vendor ID) — já identificado pelo header_id
+            let strength = AesStrength::from_code(data[4])?;
+            return Some(AesEntryInfo {
+                strength,
+                ae2: vendor_version == 2,
+            });
+        }
+
+        cursor = &cursor[4 + data_size..];
+    }
+    None
+}
+
+/// Deriva chave de criptografia + chave de autenticação + verificação de
+/// 2 bytes via PBKDF2-HMAC-SHA1 com 1000 iterações (fixo pela spec AE-1/AE-2)
+fn derive_aes_keys(password: &[u8], salt: &[u8], strength: AesStrength) -> (Vec<u8>, Vec<u8>, [u8; 2]) {
+    let key_len = strength.key_len();
+    let mut derived = vec![0u8; key_len * 2 + 2];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, salt, 1000, &mut derived);
+
+    let enc_key = derived[..key_len].to_vec();
+    let auth_key = derived[key_len..key_len * 2].to_vec();
+    let verify = [derived[key_len * 2], derived[key_len * 2 + 1]];
+    (enc_key, auth_key, verify)
+}
+
+/// Descriptografa via AES-CTR (contador little-endian de 16 bytes,
+/// começando em 1 — não em 0 — conforme a spec do WinZip AES)
+fn aes_ctr_decrypt(strength: AesStrength, key: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    use aes::cipher::{KeyIvInit, StreamCipher};
+
+    let mut iv = [0u8; 16];
+    iv[0] = 1;
+    let mut buf = ciphertext.to_vec();
+
+    // `key` chega como slice (tamanho só conhecido em runtime), então não
+    // há From<&[u8]> infalível para o GenericArray de tamanho fixo que
+    // `new` espera — new_from_slices valida o tamanho e retorna Result
+    match strength {
+        AesStrength::Aes128 => {
+            let mut cipher = ctr::Ctr128LE::<aes::Aes128>::new_from_slices(key, &iv).ok()?;
+            cipher.apply_keystream(&mut buf);
+        }
+        AesStrength::Aes192 => {
+            let mut cipher = ctr::Ctr128LE::<aes::Aes192>::new_from_slices(key, &iv).ok()?;
+            cipher.apply_keystream(&mut buf);
+        }
+        AesStrength::Aes256 => {
+            let mut cipher = ctr::Ctr128LE::<aes::Aes256>::new_from_slices(key, &iv).ok()?;
+            cipher.apply_keystream(&mut buf);
+        }
+    }
+
+    Some(buf)
+}
+
+/// Testa uma senha contra um entry WinZip AES. `raw` é o payload bruto
+/// (sem decriptação) lido via `by_index_raw`: salt + verificação de 2
+/// bytes + ciphertext + tag HMAC-SHA1 de 10 bytes. Primeiro rejeita pela
+/// verificação de 2 bytes — equivalente ao check byte do ZipCrypto
+/// clássico, descarta a maioria das senhas erradas sem tocar no
+/// ciphertext — só então descriptografa e confere a integridade.
+fn try_unlock_aes_entry(
+    raw: &[u8],
+    info: &AesEntryInfo,
+    password: &[u8],
+    expected_crc: u32,
+) -> Option<Vec<u8>> {
+    let salt_len = info.strength.salt_len();
+    if raw.len() < salt_len + 2 + 10 {
+        return None;
+    }
+
+    let salt = &raw[..salt_len];
+    let stored_verify = &raw[salt_len..salt_len + 2];
+    let ciphertext = &raw[salt_len + 2..raw.len() - 10];
+    let stored_tag = &raw[raw.len() - 10..];
+
+    let (enc_key, auth_key, verify) = derive_aes_keys(password, salt, info.strength);
+    if &verify[..] != stored_verify {
+        return None;
+    }
+
+    if info.ae2 {
+        use hmac::Mac;
+        let mut mac = match hmac::Hmac::<sha1::Sha1>::new_from_slice(&auth_key) {
+            Ok(m) => m,
+            Err(_) => return None,
+        };
+        mac.update(ciphertext);
+        if mac.verify_truncated_left(stored_tag).is_err() {
+            return None;
+        }
+    }
+
+    let plaintext = aes_ctr_decrypt(info.strength, &enc_key, ciphertext)?;
+
+    // AE-1 mantém o CRC32 original no header central; AE-2 zera esse
+    // campo e já teve a integridade validada pela tag HMAC acima
+    if !info.ae2 && crc32fast::hash(&plaintext) != expected_crc {
+        return None;
+    }
+
+    Some(plaintext)
+}
+
+/// Lê o payload bruto (sem decriptação) de um entry e, se ele usa WinZip
+/// AES (extra field 0x9901), retorna os metadados já parseados junto
+fn read_raw_aes_entry(zip_data: &[u8], entry_idx: usize) -> Option<(AesEntryInfo, Vec<u8>, u32)> {
+    let reader = Cursor::new(zip_data);
+    let mut archive = ZipArchive::new(reader).ok()?;
+    let mut file = archive.by_index_raw(entry_idx).ok()?;
+    let info = parse_aes_extra_field(file.extra_data())?;
+    let expected_crc = file.crc32();
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw).ok()?;
+    Some((info, raw, expected_crc))
+}
+
+// ============================================================
+// STREAMING SOURCE — cracking sem carregar o arquivo inteiro em RAM
+// `crack_zip_password` exige `Vec<u8>` (necessário na borda da FFI com o
+// Flutter), mas isso obriga o chamador a materializar o ZIP inteiro
+// antes de chegar aqui. Aqui o alvo é aberto a partir de qualquer
+// `Read + Seek` (ex: `std::fs::File`); só o header local do menor entry
+// criptografado é parseado e seus bytes cifrados ficam em cache — as
+// tentativas rodam direto contra esse cache, sem tocar o resto do ZIP.
+// ============================================================
+
+/// Entry criptografado cacheado: só os bytes necessários para repetir
+/// tentativas (header de 12 bytes + payload cifrado), nunca o ZIP inteiro
+struct CachedEncryptedEntry {
+    /// Bytes brutos do ZipCrypto: 12 bytes de header + payload comprimido
+    raw_data: Vec<u8>,
+    expected_crc: u32,
+    expected_size: u64,
+    /// 0 = Stored, 8 = Deflated — únicos métodos suportados pelo decoder manual abaixo
+    compression_method: u16,
+    /// Byte alto do mod-time DOS do entry — usado como fast-reject alternativo
+    /// quando o general purpose bit 3 (CRC adiada para o data descriptor) está
+    /// setado, caso em que o último byte do header de 12 bytes do ZipCrypto é
+    /// derivado do mod-time em vez do CRC32
+    mod_time_check_byte: u8,
+}
+
+/// Abre `source` (qualquer `Read + Seek`: arquivo, mmap, etc.), localiza
+/// o menor entry ZipCrypto clássico (por tamanho comprimido) e cacheia seus
+/// bytes brutos + CRC/size esperados. Só esse entry é lido para a RAM —
+/// o resto do ZIP nunca é materializado. Entries WinZip AES (método 99) são
+/// ignorados na busca pelo menor: este decoder manual não sabe lidar com
+/// eles, então escolher um deles como "o menor" e estourar depois no match
+/// de compressão esconderia um ZipCrypto perfeitamente utilizável no mesmo
+/// arquivo.
+fn locate_smallest_encrypted_entry<R: Read + std::io::Seek>(source: R) -> Result<CachedEncryptedEntry> {
+    let mut archive = ZipArchive::new(source).map_err(|e| anyhow!("Arquivo ZIP inválido: {}", e))?;
+
+    let mut smallest_idx: Option<usize> = None;
+    let mut smallest_size = u64::MAX;
+    let mut saw_only_aes = false;
+
+    for i in 0..archive.len() {
+        let is_zipcrypto = match archive.by_index_decrypt(i, b"") {
+            Ok(Err(_)) => true,
+            Ok(Ok(_)) => false,
+            Err(_) => {
+                // Entries AES (método 99) caem aqui; não são candidatos deste
+                // decoder manual, só usados para uma mensagem de erro melhor
+                if read_raw_aes_entry_size(&mut archive, i).is_some() {
+                    saw_only_aes = true;
+                }
+                false
+            }
+        };
+
+        if !is_zipcrypto {
+            continue;
+        }
+
+        let compressed_size = match archive.by_index_raw(i) {
+            Ok(f) => f.compressed_size(),
+            Err(_) => continue,
+        };
+
+        if compressed_size < smallest_size {
+            smallest_size = compressed_size;
+            smallest_idx = Some(i);
+        }
+    }
+
+    let idx = smallest_idx.ok_or_else(|| {
+        if saw_only_aes {
+            anyhow!("Todos os entries criptografados usam WinZip AES, não suportado pelo modo streaming")
+        } else {
+            anyhow!("Nenhum entry criptografado encontrado")
+        }
+    })?;
+
+    let mut file = archive
+        .by_index_raw(idx)
+        .map_err(|e| anyhow!("Falha ao ler entry {}: {}", idx, e))?;
+
+    let compression_method = match file.compression() {
+        zip::CompressionMethod::Stored => 0u16,
+        zip::CompressionMethod::Deflated => 8u16,
+        other => return Err(anyhow!("Método de compressão não suportado pelo modo streaming: {:?}", other)),
+    };
+
+    let expected_crc = file.crc32();
+    let expected_size = file.size();
+    let mod_time_check_byte = dos_time_high_byte(&file.last_modified());
+    let mut raw_data = Vec::new();
+    file.read_to_end(&mut raw_data)
+        .map_err(|e| anyhow!("Falha ao ler bytes cifrados do entry {}: {}", idx, e))?;
+
+    Ok(CachedEncryptedEntry {
+        raw_data,
+        expected_crc,
+        expected_size,
+        compression_method,
+        mod_time_check_byte,
+    })
+}
+
+/// Helper só para decidir se um entry que falhou com erro genérico no
+/// check byte do ZipCrypto é na verdade um AES não suportado por aqui
+fn read_raw_aes_entry_size<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>, idx: usize) -> Option<u64> {
+    let file = archive.by_index_raw(idx).ok()?;
+    parse_aes_extra_field(file.extra_data())?;
+    Some(file.compressed_size())
+}
+
+/// Byte alto do campo de hora DOS (bits 11-15 = hora, 5-10 = minuto, 0-4 =
+/// segundo/2), igual ao usado pelo ZipCrypto no lugar do CRC32 quando o
+/// general purpose bit 3 está setado
+fn dos_time_high_byte(dt: &zip::DateTime) -> u8 {
+    let time_word = (u16::from(dt.hour()) << 11) | (u16::from(dt.minute()) << 5) | (u16::from(dt.second()) >> 1);
+    (time_word >> 8) as u8
+}
+
+/// As 3 chaves de 32 bits da cifra ZipCrypto clássica (PKWARE stream cipher)
+struct ZipCryptoKeys {
+    key0: u32,
+    key1: u32,
+    key2: u32,
+}
+
+impl ZipCryptoKeys {
+    fn new(password: &[u8]) -> Self {
+        let mut keys = Self {
+            key0: 0x12345678,
+            key1: 0x23456789,
+            key2: 0x34567890,
+        };
+        for &b in password {
+            keys.update(b);
+        }
+        keys
+    }
+
+    fn update(&mut self, byte: u8) {
+        self.key0 = zipcrypto_crc32(self.key0, byte);
+        self.key1 = self.key1.wrapping_add(self.key0 & 0xff);
+        self.key1 = self.key1.wrapping_mul(134775813).wrapping_add(1);
+        self.key2 = zipcrypto_crc32(self.key2, (self.key1 >> 24) as u8);
+    }
+
+    fn decrypt_byte(&mut self, cipher_byte: u8) -> u8 {
+        let temp = (self.key2 | 2) as u16;
+        let keystream = ((temp.wrapping_mul(temp ^ 1)) >> 8) as u8;
+        let plain = cipher_byte ^ keystream;
+        self.update(plain);
+        plain
+    }
+}
+
+/// Tabela CRC-32 usada pelo `update_keys` do ZipCrypto clássico — não é
+/// `crc32fast::hash` (que valida o conteúdo final), é o motor usado para
+/// atualizar as 3 chaves de 32 bits a cada byte decriptado
+fn zipcrypto_crc_table() -> &'static [u32; 256] {
+    static TABLE: OnceLock<[u32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u32; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut c = i as u32;
+            for _ in 0..8 {
+                c = if c & 1 != 0 { 0xEDB88320 ^ (c >> 1) } else { c >> 1 };
+            }
+            *entry = c;
+        }
+        table
+    })
+}
+
+fn zipcrypto_crc32(crc: u32, byte: u8) -> u32 {
+    let table = zipcrypto_crc_table();
+    (crc >> 8) ^ table[((crc ^ byte as u32) & 0xff) as usize]
+}
+
+/// Testa uma senha contra o entry cacheado, sem reabrir o ZIP: decripta
+/// os 12 bytes de header (fast-reject pelo byte de checagem, igual ao
+/// check byte que `by_index_decrypt` já explora), só então decripta o
+/// restante do payload, descomprime se necessário e valida CRC32/size.
+fn try_password_against_cached(cached: &CachedEncryptedEntry, password: &[u8]) -> bool {
+    if cached.raw_data.len() < 12 {
+        return false;
+    }
+
+    let mut keys = ZipCryptoKeys::new(password);
+    let mut header = [0u8; 12];
+    for (i, &byte) in cached.raw_data[..12].iter().enumerate() {
+        header[i] = keys.decrypt_byte(byte);
+    }
+
+    // Caso comum: o último byte do header decriptado bate com o byte alto
+    // do CRC32 esperado. Mas com o general purpose bit 3 setado (CRC/size
+    // adiados para o data descriptor — comum em ZIPs gerados por streaming
+    // writers), o check byte vem do mod-time em vez do CRC32; aceita
+    // qualquer um dos dois em vez de só o caminho comum, senão senhas
+    // corretas contra esses arquivos seriam rejeitadas aqui antes mesmo de
+    // decriptar o payload
+    if header[11] != (cached.expected_crc >> 24) as u8 && header[11] != cached.mod_time_check_byte {
+        return false;
+    }
+
+    let ciphertext = &cached.raw_data[12..];
+    let mut compressed = Vec::with_capacity(ciphertext.len());
+    for &byte in ciphertext {
+        compressed.push(keys.decrypt_byte(byte));
+    }
+
+    let plaintext = match cached.compression_method {
+        0 => compressed,
+        8 => {
+            let mut out = Vec::new();
+            if flate2::read::DeflateDecoder::new(Cursor::new(&compressed))
+                .read_to_end(&mut out)
+                .is_err()
+            {
+                return false;
+            }
+            out
+        }
+        _ => return false,
+    };
+
+    plaintext.len() as u64 == cached.expected_size && crc32fast::hash(&plaintext) == cached.expected_crc
+}
+
+/// Quebra a senha de um ZIP a partir de um caminho em disco, sem
+/// carregar o arquivo inteiro na RAM — variante de `crack_zip_password`
+/// para arquivos grandes, cobrindo as fases de dicionário e força bruta
+/// (as fases combinator/Markov/máscara/checkpoint continuam exclusivas
+/// do caminho `Vec<u8>`, que já tem todo esse pipeline construído)
+pub fn crack_zip_password_from_path(
+    file_path: String,
+    config: CrackConfig,
+    progress_sink: StreamSink<CrackProgress>,
+) -> Result<()> {
+    reject_unsupported_config(&config, true, true, &progress_sink)?;
+
+    let file = std::fs::File::open(&file_path)
+        .map_err(|e| anyhow!("Falha ao abrir {}: {}", file_path, e))?;
+    let cached = locate_smallest_encrypted_entry(file)?;
+
+    let start_time = std::time::Instant::now();
+    let attempts = AtomicU64::new(0);
+    let found = AtomicBool::new(false);
+    let phase_counters = PhaseCounters::new();
+    let mut password_found: Option<String> = None;
+
+    // Fase 1: dicionário (mesma wordlist embutida; mutações built-in ou,
+    // se o usuário configurou config.rules, as regras compiladas dele —
+    // igual ao caminho principal em dictionary_attack)
+    if config.use_dictionary {
+        let wordlist: Vec<&str> = EMBEDDED_WORDLIST
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        let rules = MutationRules::parse(&config.rules);
+        let mutate = |word: &str| -> Vec<String> {
+            if rules.is_empty() {
+                generate_mutations(word)
+            } else {
+                rules.apply(word)
+            }
+        };
+
+        for word in wordlist.iter().copied().chain(config.custom_words.iter().map(String::as_str)) {
+            for candidate in mutate(word) {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                phase_counters.dictionary_attempts.fetch_add(1, Ordering::Relaxed);
+                if try_password_against_cached(&cached, candidate.as_bytes()) {
+                    password_found = Some(candidate);
+                    found.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+            if found.load(Ordering::Relaxed) {
+                break;
+            }
+        }
+    }
+
+    // Fase 2: força bruta odômetro, testando direto contra o cache
+    if !found.load(Ordering::Relaxed) {
+        let charset = CompactCharset::new(&config);
+        if !charset.is_empty() {
+            let charset_slice = charset.as_slice();
 
-/// Thread de progresso (200ms para reduzir overhead)
-fn spawn_progress_thread(
-    attempts: Arc<AtomicU64>,
-    found: Arc<AtomicBool>,
-    progress_sink: StreamSink<CrackProgress>,
-) -> std::thread::JoinHandle<()> {
-    std::thread::spawn(move || {
-        let start = std::time::Instant::now();
-        loop {
-            std::thread::sleep(std::time::Duration::from_millis(200));
+            'lengths: for length in config.min_length..=config.max_length {
+                if found.load(Ordering::Relaxed) {
+                    break;
+                }
 
-            if found.load(Ordering::Relaxed) {
-                break;
-            }
+                let chunk_size = adaptive_chunk_size(charset_slice.len(), length);
+                let total = (charset_slice.len() as u64).saturating_pow(length as u32);
+                let num_chunks = (total + chunk_size - 1) / chunk_size;
 
-            let current_attempts = attempts.load(Ordering::Relaxed);
-            let elapsed_secs = start.elapsed().as_secs_f64();
-            
-            // Report 0 speed when paused
-            let rate = if is_paused() {
-                0.0
-            } else if elapsed_secs > 0.1 {
-                current_attempts as f64 / elapsed_secs
-            } else {
-                0.0
-            };
+                let result = (0..num_chunks).into_par_iter().find_map_any(|chunk_idx| {
+                    if found.load(Ordering::Relaxed) {
+                        return None;
+                    }
+
+                    let start_idx = chunk_idx * chunk_size;
+                    let end_idx = (start_idx + chunk_size).min(total);
+                    let mut pwd_buf = vec![0u8; length];
+                    index_to_bytes(start_idx, charset_slice, &mut pwd_buf);
+
+                    for _index in start_idx..end_idx {
+                        if try_password_against_cached(&cached, &pwd_buf) {
+                            found.store(true, Ordering::Relaxed);
+                            attempts.fetch_add(1, Ordering::Relaxed);
+                            return Some(String::from_utf8_lossy(&pwd_buf).into_owned());
+                        }
+                        attempts.fetch_add(1, Ordering::Relaxed);
+                        increment_password(&mut pwd_buf, charset_slice);
+                    }
+                    None
+                });
+
+                if let Some(password) = result {
+                    password_found = Some(password);
+                    break 'lengths;
+                }
+            }
+        }
+    }
 
+    match password_found {
+        Some(password) => report_found(&progress_sink, &password, "bruteforce", start_time, &attempts, &phase_counters),
+        None => {
+            let elapsed = start_time.elapsed().as_secs_f64();
+            let total_attempts = attempts.load(Ordering::Relaxed);
             report_progress(
                 &progress_sink,
-                current_attempts,
-                String::from("..."),
-                elapsed_secs as u64,
-                rate,
+                total_attempts,
+                "Senha não encontrada".to_string(),
+                elapsed as u64,
+                if elapsed > 0.01 { total_attempts as f64 / elapsed } else { 0.0 },
+                phase_counters.to_stats(total_attempts, 100.0, Some(0)),
             );
         }
-    })
-}
-
-/// Gera senha em bytes direto no buffer (zero alocação)
-/// Usado apenas para INICIALIZAR o buffer no começo de cada chunk.
-/// Dentro do loop, usamos increment_password() que é muito mais rápido.
-#[inline(always)]
-fn index_to_bytes(mut index: u64, charset: &[u8], buf: &mut [u8]) {
-    let base = charset.len() as u64;
-    for i in (0..buf.len()).rev() {
-        buf[i] = charset[(index % base) as usize];
-        index /= base;
     }
-}
 
-/// Incrementa o buffer de senha como um odômetro.
-/// Muito mais rápido que index_to_bytes() porque:
-/// - 99%+ das vezes só muda o último byte (1 operação)
-/// - Apenas faz carry quando atinge o fim do charset
-/// - Zero divisões, zero multiplicações
-/// - Perfeitamente predizível pelo branch predictor da CPU
-#[inline(always)]
-fn increment_password(buf: &mut [u8], charset: &[u8]) {
-    let last_char = charset[charset.len() - 1];
-    // Percorre do último byte para o primeiro (como somar 1 num número)
-    for i in (0..buf.len()).rev() {
-        if buf[i] == last_char {
-            // Carry: volta para o primeiro char e continua
-            buf[i] = charset[0];
-        } else {
-            // Encontra o próximo char no charset e para
-            // Usa busca linear (charset é pequeno, cabe no L1 cache)
-            let pos = charset.iter().position(|&c| c == buf[i]).unwrap_or(0);
-            buf[i] = charset[pos + 1];
-            return;
-        }
-    }
-    // Overflow total (todas as posições fizeram carry) — não deve acontecer
-    // porque o loop externo controla o range
+    Ok(())
 }
 
 /// ULTRA SLOW but ULTRA RELIABLE password test
@@ -680,6 +2615,19 @@ fn try_unlock_ultra_safe(
     
     // Test ALL encrypted entries
     for i in 0..archive.len() {
+        // WinZip AES (extra field 0x9901) falha com erro genérico no
+        // check byte do ZipCrypto abaixo, então é detectado à parte aqui
+        if let Some((info, raw, expected_crc)) = read_raw_aes_entry(zip_data, i) {
+            total_encrypted += 1;
+            match try_unlock_aes_entry(&raw, &info, password, expected_crc) {
+                Some(_) => {
+                    decrypted_count += 1;
+                    continue;
+                }
+                None => return false,
+            }
+        }
+
         // Check if entry is encrypted by trying to decrypt with empty password
         let reader_check = Cursor::new(zip_data);
         let mut archive_check = match ZipArchive::new(reader_check) {
@@ -807,8 +2755,22 @@ fn try_unlock_fast(
     archive: &mut ZipArchive<Cursor<&[u8]>>,
     entry_idx: usize,
     password: &[u8],
-    read_buf: &mut Vec<u8>,
+    read_buf: &mut LockedBuffer,
 ) -> bool {
+    // WinZip AES (extra field 0x9901) não passa pelo check byte do
+    // ZipCrypto — detecta e trata à parte antes do caminho normal
+    if let Ok(mut raw_file) = archive.by_index_raw(entry_idx) {
+        if let Some(info) = parse_aes_extra_field(raw_file.extra_data()) {
+            let expected_crc = raw_file.crc32();
+            read_buf.zero_and_clear();
+            if raw_file.read_to_end(read_buf.inner_mut()).is_err() {
+                return false;
+            }
+            read_buf.relock();
+            return try_unlock_aes_entry(read_buf.as_slice(), &info, password, expected_crc).is_some();
+        }
+    }
+
     let pwd_str = String::from_utf8_lossy(password);
     
     // Try to decrypt
@@ -845,8 +2807,8 @@ fn try_unlock_fast(
     }
     
     // Read content
-    read_buf.clear();
-    let bytes_read = match file.read_to_end(read_buf) {
+    read_buf.zero_and_clear();
+    let bytes_read = match file.read_to_end(read_buf.inner_mut()) {
         Ok(n) => {
             println!("  ✓ Read {} bytes", n);
             n
@@ -856,25 +2818,28 @@ fn try_unlock_fast(
             return false;
         }
     };
-    
+    read_buf.relock();
+
     // Validate size
     if bytes_read as u64 != expected_size {
         println!("  ✗ Size MISMATCH (expected {}, got {})", expected_size, bytes_read);
         return false;
     }
     println!("  ✓ Size matches: {}", bytes_read);
-    
+
     // Validate CRC32
-    let actual_crc = crc32fast::hash(read_buf);
+    let actual_crc = crc32fast::hash(read_buf.as_slice());
     println!("  Actual CRC32: {:08X}", actual_crc);
-    
+
     let matches = actual_crc == expected_crc;
-    
+
     if matches {
         println!("  ✓✓✓ CRC MATCHES! ✓✓✓");
         println!("  *** PASSWORD FOUND: '{}' ***", pwd_str);
-        let preview_len = read_buf.len().min(100);
-        println!("  Content preview (first {} bytes): {:?}", preview_len, String::from_utf8_lossy(&read_buf[..preview_len]));
+        if plaintext_logging_enabled() {
+            let preview_len = read_buf.len().min(100);
+            println!("  Content preview (first {} bytes): {:?}", preview_len, String::from_utf8_lossy(&read_buf.as_slice()[..preview_len]));
+        }
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     } else {
         println!("  ✗ CRC MISMATCH - FALSE POSITIVE!");
@@ -886,6 +2851,186 @@ fn try_unlock_fast(
     matches
 }
 
+// ============================================================
+// THREADED KEYSPACE ENGINE — `try_unlock_fast` foi desenhado para
+// reaproveitar um único ZipArchive + read_buf entre tentativas, mas
+// nada o disparava em paralelo. Este pool abre exatamente um de cada
+// por thread e particiona o keyspace em faixas contíguas fixas (ao
+// invés dos chunks dinâmicos do rayon), eliminando o custo de reabrir
+// um ZipArchive a cada tentativa como `try_unlock_ultra_safe` faz.
+// ============================================================
+
+/// Varre o keyspace de um comprimento de senha usando um pool fixo de
+/// threads nativas. Cada worker recebe uma faixa contígua de índices
+/// `[start_idx, end_idx)`, abre seu próprio `ZipArchive`/`read_buf` uma
+/// única vez e reaproveita ambos via `try_unlock_fast` até exaurir sua
+/// faixa, encontrar a senha, ou observar `found` setado por outro worker.
+fn threaded_keyspace_bruteforce(
+    zip_data: &[u8],
+    entry_idx: usize,
+    charset_slice: &[u8],
+    length: usize,
+    found: &Arc<AtomicBool>,
+    attempts: &Arc<AtomicU64>,
+) -> Option<String> {
+    let total = (charset_slice.len() as u64).saturating_pow(length as u32);
+    if total == 0 {
+        return None;
+    }
+
+    let num_workers = rayon::current_num_threads().max(1);
+    let range_size = (total + num_workers as u64 - 1) / num_workers as u64;
+    // Travado e zerado no Drop — guarda a senha encontrada sem materializar
+    // uma String solta até o retorno desta função
+    let password_found: parking_lot::Mutex<Option<LockedBuffer>> = parking_lot::Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for worker_idx in 0..num_workers {
+            let start_idx = worker_idx as u64 * range_size;
+            if start_idx >= total {
+                break;
+            }
+            let end_idx = (start_idx + range_size).min(total);
+
+            let found = Arc::clone(found);
+            let attempts = Arc::clone(attempts);
+            let password_found = &password_found;
+
+            scope.spawn(move || {
+                let mut archive = match ZipArchive::new(Cursor::new(zip_data)) {
+                    Ok(a) => a,
+                    Err(_) => return,
+                };
+                let mut read_buf = LockedBuffer::with_capacity(256);
+                let mut pwd_buf = LockedBuffer::new(vec![0u8; length]);
+                index_to_bytes(start_idx, charset_slice, pwd_buf.inner_mut());
+
+                let mut local_count = 0u64;
+                for _index in start_idx..end_idx {
+                    // Checa found/cancelamento a cada 512 tentativas, mesmo
+                    // ritmo do laço single-thread em crack_zip_password
+                    if local_count & 0x1FF == 0 && local_count > 0 {
+                        if found.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        wait_if_paused();
+                    }
+
+                    if try_unlock_fast(&mut archive, entry_idx, pwd_buf.as_slice(), &mut read_buf) {
+                        found.store(true, Ordering::Relaxed);
+                        attempts.fetch_add(local_count + 1, Ordering::Relaxed);
+                        // Move o LockedBuffer inteiro em vez de converter para
+                        // String aqui — protege a senha encontrada até o
+                        // retorno da função, não só o buffer de tentativa
+                        *password_found.lock() = Some(pwd_buf);
+                        return;
+                    }
+
+                    local_count += 1;
+                    if local_count & 0x7FF == 0 {
+                        attempts.fetch_add(2048, Ordering::Relaxed);
+                        local_count -= 2048;
+                    }
+
+                    increment_password(pwd_buf.inner_mut(), charset_slice);
+                }
+
+                if local_count > 0 {
+                    attempts.fetch_add(local_count, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    // Única materialização de String: na saída desta função, já que o
+    // contrato de retorno (e o de crack_zip_password_worker_pool/report_found)
+    // exige uma String comum na fronteira do FFI
+    password_found.into_inner().map(|buf| String::from_utf8_lossy(buf.as_slice()).into_owned())
+}
+
+/// Quebra de senha por brute force usando o pool de workers nativos de
+/// `threaded_keyspace_bruteforce` em vez dos chunks dinâmicos do rayon.
+/// Cobre apenas a fase de odômetro puro — dictionary/combinator/markov/
+/// mask continuam exclusivos de `crack_zip_password`, que já os orquestra
+/// e é a via recomendada quando essas fases são necessárias. O callback
+/// de progresso (tentativas / `estimate_combinations`) e o cancelamento
+/// reaproveitam `spawn_progress_thread` e a flag `found`, assim como no
+/// motor principal.
+pub fn crack_zip_password_worker_pool(
+    file_bytes: Vec<u8>,
+    config: CrackConfig,
+    progress_sink: StreamSink<CrackProgress>,
+) -> Result<()> {
+    reject_unsupported_config(&config, false, false, &progress_sink)?;
+
+    let start_time = std::time::Instant::now();
+
+    let archive_len = ensure_valid_zip(&file_bytes, &progress_sink)?;
+    let entry_idx = match find_first_encrypted_entry(&file_bytes, archive_len, &progress_sink)? {
+        Some(idx) => idx,
+        None => return Ok(()),
+    };
+
+    let charset = CompactCharset::new(&config);
+    if charset.is_empty() {
+        report_error(&progress_sink, "ERRO: Nenhum caractere selecionado".to_string());
+        return Err(anyhow!("Nenhum caractere selecionado para teste"));
+    }
+    let charset_slice = charset.as_slice();
+
+    let total_keyspace = estimate_combinations(config.clone());
+    let attempts = Arc::new(AtomicU64::new(0));
+    let found = Arc::new(AtomicBool::new(false));
+    let phase_counters = Arc::new(PhaseCounters::new());
+
+    let progress_thread = spawn_progress_thread(
+        Arc::clone(&attempts),
+        Arc::clone(&found),
+        progress_sink.clone(),
+        Arc::clone(&phase_counters),
+        total_keyspace,
+        None,
+        start_time,
+    );
+
+    let zip_data: &[u8] = &file_bytes;
+    let mut password_found: Option<String> = None;
+
+    for length in config.min_length..=config.max_length {
+        if found.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Some(pwd) =
+            threaded_keyspace_bruteforce(zip_data, entry_idx, charset_slice, length, &found, &attempts)
+        {
+            password_found = Some(pwd);
+            break;
+        }
+    }
+
+    found.store(true, Ordering::Relaxed);
+    let _ = progress_thread.join();
+
+    match password_found {
+        Some(pwd) => {
+            report_found(&progress_sink, &pwd, "bruteforce", start_time, &attempts, &phase_counters);
+        }
+        None => {
+            let total_attempts = attempts.load(Ordering::Relaxed);
+            report_progress(
+                &progress_sink,
+                total_attempts,
+                "Senha não encontrada".to_string(),
+                start_time.elapsed().as_secs(),
+                0.0,
+                phase_counters.to_stats(total_attempts, 100.0, Some(0)),
+            );
+        }
+    }
+
+    Ok(())
+}
+
 // ============================================================
 // FUNÇÕES AUXILIARES
 // ============================================================
@@ -917,6 +3062,22 @@ fn try_unlock(bytes: &[u8], password: &str) -> bool {
     match ZipArchive::new(reader) {
         Ok(mut archive) => {
             for i in 0..archive.len() {
+                // WinZip AES (extra field 0x9901) não é suportado por
+                // by_index_decrypt — trata à parte antes do caminho ZipCrypto
+                if let Ok(mut raw_file) = archive.by_index_raw(i) {
+                    if let Some(info) = parse_aes_extra_field(raw_file.extra_data()) {
+                        let expected_crc = raw_file.crc32();
+                        let mut raw = Vec::new();
+                        if raw_file.read_to_end(&mut raw).is_err() {
+                            continue;
+                        }
+                        if try_unlock_aes_entry(&raw, &info, password.as_bytes(), expected_crc).is_some() {
+                            return true;
+                        }
+                        continue;
+                    }
+                }
+
                 match archive.by_index_decrypt(i, password.as_bytes()) {
                     Ok(Ok(mut file)) => {
                         let expected_crc = file.crc32();
@@ -944,24 +3105,197 @@ fn try_unlock(bytes: &[u8], password: &str) -> bool {
 
 /// Estima o número total de combinações
 pub fn estimate_combinations(config: CrackConfig) -> u64 {
-    let charset = build_charset(&config);
-    let charset_size = charset.len() as u64;
-
-    let mut total = 0u64;
-    for length in config.min_length..=config.max_length {
-        total = total.saturating_add(charset_size.saturating_pow(length as u32));
-    }
+    // Com máscara, o keyspace é o produto dos tamanhos de classe por
+    // posição (base mista) em vez de charset_size^length — uma máscara
+    // com posições literais ou classes estreitas (ex: só dígitos nas
+    // últimas posições) reduz drasticamente o total frente ao charset
+    // uniforme, e a ETA reportada precisa refletir isso.
+    let mut total = match config.mask.as_deref().and_then(|spec| Mask::parse(spec).ok()) {
+        Some(mask) => mask.total_combinations(),
+        None => {
+            let charset = build_charset(&config);
+            let charset_size = charset.len() as u64;
+            (config.min_length..=config.max_length)
+                .fold(0u64, |acc, length| acc.saturating_add(charset_size.saturating_pow(length as u32)))
+        }
+    };
 
     // Add dictionary estimate if enabled
     if config.use_dictionary {
-        // ~3548 words in embedded wordlist + custom words, ~12 mutations each
+        // ~3548 words in embedded wordlist + custom words, ~19 mutações cada
+        // (ver generate_mutations: original/upper/capitalize/10 sufixos/5 anos/leet)
         let dict_count = EMBEDDED_WORDLIST.lines().count() + config.custom_words.len();
-        total = total.saturating_add((dict_count * 12) as u64);
+        total = total.saturating_add((dict_count * 19) as u64);
     }
 
     total
 }
 
+// ============================================================
+// BENCHMARK MODE — mede throughput do caminho quente (odômetro +
+// try_password_against_cached) contra um entry ZipCrypto sintético,
+// sem depender de um arquivo real do usuário
+// ============================================================
+
+/// Janela de medição (tempo de parede) do benchmark, para o único comprimento medido
+const BENCHMARK_WINDOW: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Relatório de uma rodada de benchmark, para a UI calibrar expectativas
+/// e para os mantenedores detectarem regressões no odômetro/chunking
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub charset_size: usize,
+    pub chunk_size: u64,
+    pub passwords_per_second: f64,
+    /// `passwords_per_second` dividido pelo número de threads do rayon,
+    /// para comparar o ganho de paralelismo entre dispositivos
+    pub per_core_passwords_per_second: f64,
+    pub cores_used: usize,
+    pub duration_seconds: f64,
+}
+
+/// Payload fixo do entry sintético do benchmark
+const BENCHMARK_PAYLOAD: &[u8] = b"rapid-crak benchmark payload";
+
+/// Cifra um byte de texto claro com ZipCrypto clássico — o mesmo keystream
+/// de `ZipCryptoKeys::decrypt_byte`, só que a atualização das chaves usa o
+/// byte claro diretamente em vez do byte decriptado (na decriptação os
+/// dois já são o mesmo valor; aqui ainda não temos um ciphertext pra
+/// decriptar, por isso o encoder próprio em vez de reusar decrypt_byte)
+fn zipcrypto_encrypt_byte(keys: &mut ZipCryptoKeys, plain: u8) -> u8 {
+    let temp = (keys.key2 | 2) as u16;
+    let keystream = ((temp.wrapping_mul(temp ^ 1)) >> 8) as u8;
+    let cipher = plain ^ keystream;
+    keys.update(plain);
+    cipher
+}
+
+/// Monta um `CachedEncryptedEntry` ZipCrypto em memória com uma senha
+/// conhecida, usado como alvo fixo do benchmark. Em vez de gerar um ZIP de
+/// verdade (a dependência `zip` usada por este crate só lê, nunca escreve,
+/// entries criptografados — não há `start_file_with_password` nem
+/// equivalente), cifra o payload à mão com o mesmo ZipCrypto clássico que
+/// `try_password_against_cached` já sabe validar, dispensando qualquer
+/// round-trip por `ZipArchive`/`ZipWriter`.
+fn build_synthetic_encrypted_entry(password: &[u8]) -> CachedEncryptedEntry {
+    let expected_crc = crc32fast::hash(BENCHMARK_PAYLOAD);
+    let expected_size = BENCHMARK_PAYLOAD.len() as u64;
+
+    let mut keys = ZipCryptoKeys::new(password);
+    let mut raw_data = Vec::with_capacity(12 + BENCHMARK_PAYLOAD.len());
+
+    // Header de 12 bytes do ZipCrypto: só o último byte importa (check byte
+    // contra o CRC), os 11 anteriores podem ser qualquer coisa
+    for _ in 0..11 {
+        raw_data.push(zipcrypto_encrypt_byte(&mut keys, 0x55));
+    }
+    raw_data.push(zipcrypto_encrypt_byte(&mut keys, (expected_crc >> 24) as u8));
+
+    for &b in BENCHMARK_PAYLOAD {
+        raw_data.push(zipcrypto_encrypt_byte(&mut keys, b));
+    }
+
+    CachedEncryptedEntry {
+        raw_data,
+        expected_crc,
+        expected_size,
+        compression_method: 0, // Stored
+        mod_time_check_byte: 0,
+    }
+}
+
+/// Mede passwords/s do caminho quente contra um ZIP sintético (o resultado
+/// de cada tentativa é descartado, só interessa a velocidade de iteração), no
+/// comprimento `config.max_length`, reportando números parciais a cada
+/// volta do keyspace via `progress_sink` sob a fase `"benchmark"`
+pub fn benchmark(
+    config: CrackConfig,
+    progress_sink: StreamSink<CrackProgress>,
+) -> Result<BenchmarkReport> {
+    let charset = CompactCharset::new(&config);
+    if charset.is_empty() {
+        return Err(anyhow!("Charset vazio: habilite ao menos uma categoria de caracteres"));
+    }
+    let charset_slice = charset.as_slice();
+    let length = config.max_length.max(1);
+    let chunk_size = adaptive_chunk_size(charset_slice.len(), length);
+    let cores_used = rayon::current_num_threads();
+
+    let cached = build_synthetic_encrypted_entry(b"bk");
+    let attempts = Arc::new(AtomicU64::new(0));
+    let start_time = std::time::Instant::now();
+    let deadline = start_time + BENCHMARK_WINDOW;
+
+    let total = (charset_slice.len() as u64).saturating_pow(length as u32);
+    let num_chunks = (total + chunk_size - 1) / chunk_size;
+
+    // Repete as voltas pelo keyspace de `length` até a janela de tempo
+    // fixa se esgotar — o objetivo é medir velocidade, não encontrar nada
+    while std::time::Instant::now() < deadline {
+        (0..num_chunks).into_par_iter().for_each(|chunk_idx| {
+            if std::time::Instant::now() >= deadline {
+                return;
+            }
+
+            let start_idx = chunk_idx * chunk_size;
+            let end_idx = (start_idx + chunk_size).min(total);
+
+            let mut pwd_buf = vec![0u8; length];
+            let mut local_count = 0u64;
+            index_to_bytes(start_idx, charset_slice, &mut pwd_buf);
+
+            for _index in start_idx..end_idx {
+                if local_count & 0x1FF == 0 && local_count > 0 && std::time::Instant::now() >= deadline {
+                    break;
+                }
+
+                try_password_against_cached(&cached, &pwd_buf);
+                local_count += 1;
+
+                if local_count & 0x7FF == 0 {
+                    attempts.fetch_add(2048, Ordering::Relaxed);
+                    local_count -= 2048;
+                }
+
+                increment_password(&mut pwd_buf, charset_slice);
+            }
+
+            if local_count > 0 {
+                attempts.fetch_add(local_count, Ordering::Relaxed);
+            }
+        });
+
+        let elapsed = start_time.elapsed().as_secs_f64();
+        let total_attempts = attempts.load(Ordering::Relaxed);
+        let rate = if elapsed > 0.01 { total_attempts as f64 / elapsed } else { 0.0 };
+        let _ = progress_sink.add(CrackProgress {
+            attempts: total_attempts,
+            current_password: String::new(),
+            elapsed_seconds: elapsed as u64,
+            passwords_per_second: rate,
+            phase: "benchmark".to_string(),
+            stats: CrackStats::default(),
+        });
+    }
+
+    let duration_seconds = start_time.elapsed().as_secs_f64();
+    let total_attempts = attempts.load(Ordering::Relaxed);
+    let passwords_per_second = if duration_seconds > 0.0 {
+        total_attempts as f64 / duration_seconds
+    } else {
+        0.0
+    };
+
+    Ok(BenchmarkReport {
+        charset_size: charset_slice.len(),
+        chunk_size,
+        passwords_per_second,
+        per_core_passwords_per_second: passwords_per_second / cores_used.max(1) as f64,
+        cores_used,
+        duration_seconds,
+    })
+}
+
 /// Debug function to test a specific password and see what happens
 #[flutter_rust_bridge::frb(sync)]
 pub fn debug_password_test(file_bytes: Vec<u8>, password: String) -> String {
@@ -1067,10 +3401,12 @@ pub fn test_specific_password(file_bytes: Vec<u8>, password: String) -> String {
                             results.push_str(&format!("  ✗ VALIDATION FAILED\n"));
                         }
                         
-                        let preview_len = buf.len().min(200);
-                        results.push_str(&format!("  Content (first {} bytes): {:?}\n", 
-                            preview_len, String::from_utf8_lossy(&buf[..preview_len])));
-                        
+                        if plaintext_logging_enabled() {
+                            let preview_len = buf.len().min(200);
+                            results.push_str(&format!("  Content (first {} bytes): {:?}\n",
+                                preview_len, String::from_utf8_lossy(&buf[..preview_len])));
+                        }
+
                         return results;
                     }
                     Err(e) => {